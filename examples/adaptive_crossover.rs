@@ -0,0 +1,72 @@
+//! Finds the removal density at which `VecExt::retain_mut_swap` stops being faster than
+//! the backshift algorithm of `RetainMut::retain_mut`, which is where
+//! `VecExt::retain_mut_unordered_adaptive` switches between them.
+//!
+//! Run with `cargo run --release --example adaptive_crossover`.
+
+extern crate retain_mut;
+
+use std::hint::black_box;
+use std::time::Instant;
+
+#[allow(deprecated)]
+use retain_mut::RetainMut;
+use retain_mut::VecExt;
+
+const RUNS: usize = 30;
+const BYTES: usize = 8 << 20;
+
+// Runs `f` on fresh copies of `src`, and returns the best time in milliseconds.
+fn best<T: Clone, F: FnMut(&mut Vec<T>)>(src: &[T], mut f: F) -> f64 {
+    let mut best = f64::MAX;
+    for _ in 0..RUNS {
+        let mut v = src.to_vec();
+        let start = Instant::now();
+        f(&mut v);
+        let elapsed = start.elapsed();
+        best = best.min(elapsed.as_secs() as f64 * 1e3 + elapsed.subsec_nanos() as f64 / 1e6);
+        black_box(v);
+    }
+    best
+}
+
+// Spreads the removed elements evenly but irregularly over the vector.
+fn removed(key: u64, percent: u64) -> bool {
+    key.wrapping_mul(0x9e37_79b9_7f4a_7c15) >> 32 & 0xffff < percent * 0x10000 / 100
+}
+
+#[allow(deprecated)]
+fn bench<T: Clone>(name: &str, make: fn(u64) -> T, key: fn(&mut T) -> &mut u64) {
+    let len = BYTES / std::mem::size_of::<T>();
+    let src: Vec<T> = (0..len as u64).map(make).collect();
+    let mut crossover = None;
+    for percent in (1..10).map(|x| x * 10) {
+        let pred = |x: &mut T| {
+            let k = key(x);
+            *k = k.wrapping_add(len as u64);
+            !removed(*k, percent)
+        };
+        let backshift = best(&src, |v| RetainMut::retain_mut(v, pred));
+        let swap = best(&src, |v| v.retain_mut_swap(pred));
+        println!(
+            "{:>16} remove {:>2}%  backshift: {:7.3} ms  swap: {:7.3} ms",
+            name, percent, backshift, swap
+        );
+        if crossover.is_none() && swap >= backshift {
+            crossover = Some(percent);
+        }
+    }
+    match crossover {
+        Some(percent) => println!("{:>16} swap removal stops winning at {}%", name, percent),
+        None => println!("{:>16} swap removal always wins", name),
+    }
+}
+
+fn main() {
+    bench("u64", |i| i, |x| x);
+    bench("[u64; 2]", |i| [i; 2], |x| &mut x[0]);
+    bench("[u64; 4]", |i| [i; 4], |x| &mut x[0]);
+    bench("[u64; 8]", |i| [i; 8], |x| &mut x[0]);
+    bench("[u64; 16]", |i| [i; 16], |x| &mut x[0]);
+    bench("[u64; 32]", |i| [i; 32], |x| &mut x[0]);
+}
@@ -0,0 +1,668 @@
+//! The backshift algorithm shared by vector-like collections.
+
+// Not all variants are used by every vector-like collection.
+#![cfg_attr(not(feature = "alloc"), allow(dead_code))]
+
+use core::cmp;
+use core::convert::Infallible;
+#[cfg(feature = "async")]
+use core::future::Future;
+use core::ops::Range;
+#[cfg(feature = "async")]
+use core::pin::Pin;
+use core::ptr;
+use core::slice;
+#[cfg(feature = "async")]
+use core::task::{Context, Poll};
+
+/// Vector-like collection the backshift algorithm can operate on.
+///
+/// # Safety
+///
+/// Implementors must store their elements contiguously like `Vec`:
+/// `as_mut_ptr` must return a pointer to the first of at least `len` valid elements,
+/// and `set_len` must only change the length, without touching any element.
+pub unsafe trait VecLike {
+    type Item;
+
+    fn len(&self) -> usize;
+
+    fn as_mut_ptr(&mut self) -> *mut Self::Item;
+
+    unsafe fn set_len(&mut self, len: usize);
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T> VecLike for ::alloc::vec::Vec<T> {
+    type Item = T;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.as_mut_ptr()
+    }
+
+    unsafe fn set_len(&mut self, len: usize) {
+        self.set_len(len);
+    }
+}
+
+// Vec: [Kept, Kept, Hole, Hole, Hole, Hole, Unchecked, Unchecked]
+//      |<-              processed len   ->| ^- next to check
+//                  |<-  deleted cnt     ->|
+//      |<-              original_len                          ->|
+// Kept: Elements which predicate returns true on.
+// Hole: Moved or dropped element slot.
+// Unchecked: Unchecked valid elements.
+//
+// This drop guard will be invoked when predicate or `drop` of element panicked.
+// It shifts unchecked elements to cover holes and `set_len` to the correct length.
+// In cases when predicate and `drop` never panick, it will be optimized out.
+struct BackshiftOnDrop<'a, V: VecLike + ?Sized> {
+    v: &'a mut V,
+    processed_len: usize,
+    deleted_cnt: usize,
+    original_len: usize,
+}
+
+impl<V: VecLike + ?Sized> Drop for BackshiftOnDrop<'_, V> {
+    fn drop(&mut self) {
+        if self.deleted_cnt > 0 {
+            // SAFETY: Trailing unchecked items must be valid since we never touch them.
+            unsafe {
+                let p = self.v.as_mut_ptr();
+                ptr::copy(
+                    p.add(self.processed_len),
+                    p.add(self.processed_len - self.deleted_cnt),
+                    self.original_len - self.processed_len,
+                );
+            }
+        }
+        // SAFETY: After filling holes, all items are in contiguous memory.
+        unsafe {
+            self.v.set_len(self.original_len - self.deleted_cnt);
+        }
+    }
+}
+
+/// Returns the number of removed elements.
+pub fn retain_mut<V, F>(v: &mut V, mut f: F) -> usize
+where
+    V: VecLike + ?Sized,
+    F: FnMut(&mut V::Item) -> bool,
+{
+    match try_retain_mut::<V, _, Infallible>(v, |x| Ok(f(x))) {
+        Ok(deleted_cnt) => deleted_cnt,
+        Err(e) => match e {},
+    }
+}
+
+/// Returns the number of removed elements, unless predicate failed.
+pub fn try_retain_mut<V, F, E>(v: &mut V, f: F) -> Result<usize, E>
+where
+    V: VecLike + ?Sized,
+    F: FnMut(&mut V::Item) -> Result<bool, E>,
+{
+    let len = v.len();
+    try_retain_mut_range(v, 0..len, f)
+}
+
+/// Returns the number of removed elements.
+pub fn retain_mut_range<V, F>(v: &mut V, range: Range<usize>, mut f: F) -> usize
+where
+    V: VecLike + ?Sized,
+    F: FnMut(&mut V::Item) -> bool,
+{
+    match try_retain_mut_range::<V, _, Infallible>(v, range, |x| Ok(f(x))) {
+        Ok(deleted_cnt) => deleted_cnt,
+        Err(e) => match e {},
+    }
+}
+
+// The implementation is based on
+// https://github.com/rust-lang/rust/blob/03c8ffaacb040a8753ef8e1accea701bc9f5be85/library/alloc/src/vec/mod.rs#L1478-L1569
+/// Only elements in `range` are visited, and the elements after it are shifted to fill the holes.
+/// Returns the number of removed elements, unless predicate failed.
+pub fn try_retain_mut_range<V, F, E>(v: &mut V, range: Range<usize>, mut f: F) -> Result<usize, E>
+where
+    V: VecLike + ?Sized,
+    F: FnMut(&mut V::Item) -> Result<bool, E>,
+{
+    let original_len = v.len();
+    debug_assert!(range.start <= range.end && range.end <= original_len);
+    // Avoid double drop if the drop guard is not executed,
+    // since we may make some holes during the process.
+    unsafe { v.set_len(0) };
+
+    let mut g = BackshiftOnDrop {
+        v,
+        processed_len: range.start,
+        deleted_cnt: 0,
+        original_len,
+    };
+
+    fn process_loop<F, V, E, const DELETED: bool>(
+        end: usize,
+        f: &mut F,
+        g: &mut BackshiftOnDrop<'_, V>,
+    ) -> Result<(), E>
+    where
+        V: VecLike + ?Sized,
+        F: FnMut(&mut V::Item) -> Result<bool, E>,
+    {
+        while g.processed_len != end {
+            // Only take the pointer once per element, since for some collections,
+            // `as_mut_ptr` may invalidate pointers previously taken.
+            let p = g.v.as_mut_ptr();
+            // SAFETY: Unchecked element must be valid.
+            let cur = unsafe { &mut *p.add(g.processed_len) };
+            // On error, return without advancing, so the drop guard keeps this element.
+            if !f(cur)? {
+                // Advance early to avoid double drop if `drop_in_place` panicked.
+                g.processed_len += 1;
+                g.deleted_cnt += 1;
+                // SAFETY: We never touch this element again after dropped.
+                unsafe { ptr::drop_in_place(cur) };
+                // We already advanced the counter.
+                if DELETED {
+                    continue;
+                } else {
+                    break;
+                }
+            }
+            // Kept elements are deliberately moved one by one right after the predicate returns,
+            // while they're still hot in the cache. Leaving them in place to move each run with
+            // a single `ptr::copy` when the next element is rejected was measured to be 35-80%
+            // slower for 8- and 32-byte elements at any removal rate, and no faster for 256-byte
+            // ones, since the run has to be read again, and every run takes a `memmove` call.
+            if DELETED {
+                // SAFETY: `deleted_cnt` > 0, so the hole slot must not overlap with current element.
+                // We use copy for move, and never touch this element again.
+                unsafe {
+                    let hole_slot = p.add(g.processed_len - g.deleted_cnt);
+                    ptr::copy_nonoverlapping(cur, hole_slot, 1);
+                }
+            }
+            g.processed_len += 1;
+        }
+        Ok(())
+    }
+
+    // Stage 1: Nothing was deleted.
+    process_loop::<F, V, E, false>(range.end, &mut f, &mut g)?;
+
+    // Stage 2: Some elements were deleted.
+    process_loop::<F, V, E, true>(range.end, &mut f, &mut g)?;
+
+    // All item are processed. This can be optimized to `set_len` by LLVM.
+    let deleted_cnt = g.deleted_cnt;
+    drop(g);
+    Ok(deleted_cnt)
+}
+
+// Vec: [Unchecked, Unchecked, Hole, Hole, Hole, Kept, Kept]
+//      |<- unchecked len  ->| ^- previous to check
+//                           |<- deleted cnt ->|
+//      |<-              original_len                  ->|
+// Kept: Elements which predicate returns true on.
+// Hole: Moved or dropped element slot.
+// Unchecked: Unchecked valid elements.
+//
+// This drop guard will be invoked when all elements are processed,
+// or when predicate or `drop` of element panicked.
+// It shifts kept elements to cover holes and `set_len` to the correct length.
+struct BackshiftRevOnDrop<'a, V: VecLike + ?Sized> {
+    v: &'a mut V,
+    unchecked_len: usize,
+    deleted_cnt: usize,
+    original_len: usize,
+}
+
+impl<V: VecLike + ?Sized> Drop for BackshiftRevOnDrop<'_, V> {
+    fn drop(&mut self) {
+        if self.deleted_cnt > 0 {
+            // SAFETY: Trailing kept items must be valid since we only move valid items there.
+            unsafe {
+                let p = self.v.as_mut_ptr();
+                ptr::copy(
+                    p.add(self.unchecked_len + self.deleted_cnt),
+                    p.add(self.unchecked_len),
+                    self.original_len - self.unchecked_len - self.deleted_cnt,
+                );
+            }
+        }
+        // SAFETY: After filling holes, all items are in contiguous memory.
+        unsafe {
+            self.v.set_len(self.original_len - self.deleted_cnt);
+        }
+    }
+}
+
+/// Like `retain_mut` but decides on a batch of elements at a time.
+///
+/// The predicate is given up to `mask.len()` unchecked elements, and sets the corresponding
+/// items in the mask, which are initialized to `true`, to `false` for elements to remove.
+pub fn retain_mut_batched<V, F>(v: &mut V, mask: &mut [bool], mut f: F)
+where
+    V: VecLike + ?Sized,
+    F: FnMut(&mut [V::Item], &mut [bool]),
+{
+    let original_len = v.len();
+    // Avoid double drop if the drop guard is not executed,
+    // since we may make some holes during the process.
+    unsafe { v.set_len(0) };
+
+    let mut g = BackshiftOnDrop {
+        v,
+        processed_len: 0,
+        deleted_cnt: 0,
+        original_len,
+    };
+
+    while g.processed_len != original_len {
+        let n = cmp::min(mask.len(), original_len - g.processed_len);
+        let mask = &mut mask[..n];
+        for keep in mask.iter_mut() {
+            *keep = true;
+        }
+        let p = g.v.as_mut_ptr();
+        // SAFETY: Unchecked elements must be valid.
+        let batch = unsafe { slice::from_raw_parts_mut(p.add(g.processed_len), n) };
+        // Decide phase: nothing is moved if the predicate panics.
+        f(batch, mask);
+        // Compact phase.
+        for &keep in mask.iter() {
+            let cur = unsafe { p.add(g.processed_len) };
+            if !keep {
+                // Advance early to avoid double drop if `drop_in_place` panicked.
+                g.processed_len += 1;
+                g.deleted_cnt += 1;
+                // SAFETY: We never touch this element again after dropped.
+                unsafe { ptr::drop_in_place(cur) };
+                continue;
+            }
+            if g.deleted_cnt > 0 {
+                // SAFETY: `deleted_cnt` > 0, so the hole slot must not overlap with current element.
+                // We use copy for move, and never touch this element again.
+                unsafe { ptr::copy_nonoverlapping(cur, p.add(g.processed_len - g.deleted_cnt), 1) };
+            }
+            g.processed_len += 1;
+        }
+    }
+}
+
+/// Like `retain_mut` but moves each element into the predicate,
+/// which returns the element to retain in its place, or `None` to remove it.
+pub fn retain_mut_replace<V, F>(v: &mut V, mut f: F)
+where
+    V: VecLike + ?Sized,
+    F: FnMut(V::Item) -> Option<V::Item>,
+{
+    let original_len = v.len();
+    // Avoid double drop if the drop guard is not executed,
+    // since we may make some holes during the process.
+    unsafe { v.set_len(0) };
+
+    let mut g = BackshiftOnDrop {
+        v,
+        processed_len: 0,
+        deleted_cnt: 0,
+        original_len,
+    };
+
+    while g.processed_len != original_len {
+        let p = g.v.as_mut_ptr();
+        // SAFETY: Unchecked element must be valid.
+        let cur = unsafe { ptr::read(p.add(g.processed_len)) };
+        // The element is moved out, so count its slot as a hole before calling the predicate.
+        // If the predicate panics, the element is dropped by the predicate itself.
+        g.processed_len += 1;
+        g.deleted_cnt += 1;
+        if let Some(new) = f(cur) {
+            g.deleted_cnt -= 1;
+            // SAFETY: The slot of the current element is a hole, and so is every slot
+            // from the end of the kept elements up to it.
+            unsafe { ptr::write(p.add(g.processed_len - 1 - g.deleted_cnt), new) };
+        }
+    }
+}
+
+/// Like `retain_mut` but also passes the last retained element to the predicate.
+pub fn retain_mut_windowed<V, F>(v: &mut V, mut f: F)
+where
+    V: VecLike + ?Sized,
+    F: FnMut(Option<&V::Item>, &mut V::Item) -> bool,
+{
+    let original_len = v.len();
+    // Avoid double drop if the drop guard is not executed,
+    // since we may make some holes during the process.
+    unsafe { v.set_len(0) };
+
+    let mut g = BackshiftOnDrop {
+        v,
+        processed_len: 0,
+        deleted_cnt: 0,
+        original_len,
+    };
+
+    while g.processed_len != original_len {
+        let p = g.v.as_mut_ptr();
+        let kept_len = g.processed_len - g.deleted_cnt;
+        // SAFETY: Kept elements are moved to `0..kept_len` already,
+        // so the last one never overlaps with the current element.
+        let prev = if kept_len > 0 {
+            Some(unsafe { &*p.add(kept_len - 1) })
+        } else {
+            None
+        };
+        // SAFETY: Unchecked element must be valid.
+        let cur = unsafe { &mut *p.add(g.processed_len) };
+        if !f(prev, cur) {
+            // Advance early to avoid double drop if `drop_in_place` panicked.
+            g.processed_len += 1;
+            g.deleted_cnt += 1;
+            // SAFETY: We never touch this element again after dropped.
+            unsafe { ptr::drop_in_place(cur) };
+            continue;
+        }
+        if g.deleted_cnt > 0 {
+            // SAFETY: `deleted_cnt` > 0, so the hole slot must not overlap with current element.
+            // We use copy for move, and never touch this element again.
+            unsafe { ptr::copy_nonoverlapping(cur, p.add(kept_len), 1) };
+        }
+        g.processed_len += 1;
+    }
+}
+
+/// Like `retain_mut` but visits elements from back to front.
+pub fn retain_mut_rev<V, F>(v: &mut V, mut f: F)
+where
+    V: VecLike + ?Sized,
+    F: FnMut(&mut V::Item) -> bool,
+{
+    let original_len = v.len();
+    // Avoid double drop if the drop guard is not executed,
+    // since we may make some holes during the process.
+    unsafe { v.set_len(0) };
+
+    let mut g = BackshiftRevOnDrop {
+        v,
+        unchecked_len: original_len,
+        deleted_cnt: 0,
+        original_len,
+    };
+
+    fn process_loop<F, V, const DELETED: bool>(f: &mut F, g: &mut BackshiftRevOnDrop<'_, V>)
+    where
+        V: VecLike + ?Sized,
+        F: FnMut(&mut V::Item) -> bool,
+    {
+        while g.unchecked_len != 0 {
+            let p = g.v.as_mut_ptr();
+            // SAFETY: Unchecked element must be valid.
+            let cur = unsafe { &mut *p.add(g.unchecked_len - 1) };
+            if !f(cur) {
+                // Advance early to avoid double drop if `drop_in_place` panicked.
+                g.unchecked_len -= 1;
+                g.deleted_cnt += 1;
+                // SAFETY: We never touch this element again after dropped.
+                unsafe { ptr::drop_in_place(cur) };
+                // We already advanced the counter.
+                if DELETED {
+                    continue;
+                } else {
+                    break;
+                }
+            }
+            if DELETED {
+                // SAFETY: `deleted_cnt` > 0, so the hole slot must not overlap with current element.
+                // We use copy for move, and never touch this element again.
+                unsafe {
+                    let hole_slot = p.add(g.unchecked_len - 1 + g.deleted_cnt);
+                    ptr::copy_nonoverlapping(cur, hole_slot, 1);
+                }
+            }
+            g.unchecked_len -= 1;
+        }
+    }
+
+    // Stage 1: Nothing was deleted.
+    process_loop::<F, V, false>(&mut f, &mut g);
+
+    // Stage 2: Some elements were deleted.
+    process_loop::<F, V, true>(&mut f, &mut g);
+
+    // All item are processed. The drop guard moves kept elements to the front.
+    drop(g);
+}
+
+/// Like `retain_mut` but operates on two collections of the same length in lockstep.
+pub fn retain_mut_zip<A, B, F>(a: &mut A, b: &mut B, mut f: F)
+where
+    A: VecLike + ?Sized,
+    B: VecLike + ?Sized,
+    F: FnMut(&mut A::Item, &mut B::Item) -> bool,
+{
+    let original_len = a.len();
+    assert_eq!(original_len, b.len(), "lengths of the collections differ");
+    // Avoid double drop if the drop guards are not executed,
+    // since we may make some holes during the process.
+    unsafe {
+        a.set_len(0);
+        b.set_len(0);
+    }
+
+    let mut ga = BackshiftOnDrop {
+        v: a,
+        processed_len: 0,
+        deleted_cnt: 0,
+        original_len,
+    };
+    let mut gb = BackshiftOnDrop {
+        v: b,
+        processed_len: 0,
+        deleted_cnt: 0,
+        original_len,
+    };
+
+    while ga.processed_len != original_len {
+        let i = ga.processed_len;
+        let pa = ga.v.as_mut_ptr();
+        let pb = gb.v.as_mut_ptr();
+        // SAFETY: Unchecked elements must be valid.
+        let (cur_a, cur_b) = unsafe { (&mut *pa.add(i), &mut *pb.add(i)) };
+        if !f(cur_a, cur_b) {
+            // Advance early to avoid double drop if `drop_in_place` panicked.
+            // If dropping `cur_a` panics, `cur_b` is leaked.
+            ga.processed_len += 1;
+            ga.deleted_cnt += 1;
+            gb.processed_len += 1;
+            gb.deleted_cnt += 1;
+            // SAFETY: We never touch these elements again after dropped.
+            unsafe {
+                ptr::drop_in_place(cur_a);
+                ptr::drop_in_place(cur_b);
+            }
+            continue;
+        }
+        if ga.deleted_cnt > 0 {
+            // SAFETY: `deleted_cnt` > 0, so the hole slots must not overlap with current elements.
+            // We use copy for move, and never touch these elements again.
+            unsafe {
+                ptr::copy_nonoverlapping(cur_a, pa.add(i - ga.deleted_cnt), 1);
+                ptr::copy_nonoverlapping(cur_b, pb.add(i - gb.deleted_cnt), 1);
+            }
+        }
+        ga.processed_len += 1;
+        gb.processed_len += 1;
+    }
+}
+
+// The implementation is based on
+// https://github.com/rust-lang/rust/blob/17067e9ac6d7e98f18d4f2a8ed3ab459c4a17c4e/library/alloc/src/vec/extract_if.rs
+pub struct ExtractIf<'a, V: VecLike + ?Sized, F> {
+    v: &'a mut V,
+    /// The index of the item that will be inspected by the next call to `next`.
+    idx: usize,
+    /// The number of items that have been drained (removed) thus far.
+    del: usize,
+    /// The original length of `v` prior to draining.
+    old_len: usize,
+    /// The filter test predicate.
+    pred: F,
+}
+
+impl<'a, V: VecLike + ?Sized, F> ExtractIf<'a, V, F> {
+    pub fn new(v: &'a mut V, pred: F) -> Self {
+        let old_len = v.len();
+        // Guard against the vec getting leaked (leak amplification).
+        unsafe { v.set_len(0) };
+        ExtractIf {
+            v,
+            idx: 0,
+            del: 0,
+            old_len,
+            pred,
+        }
+    }
+}
+
+impl<V: VecLike + ?Sized, F> Iterator for ExtractIf<'_, V, F>
+where
+    F: FnMut(&mut V::Item) -> bool,
+{
+    type Item = V::Item;
+
+    fn next(&mut self) -> Option<V::Item> {
+        while self.idx < self.old_len {
+            let i = self.idx;
+            let p = self.v.as_mut_ptr();
+            // SAFETY: Unchecked element must be valid.
+            let cur = unsafe { &mut *p.add(i) };
+            let drained = (self.pred)(cur);
+            // Update the index *after* the predicate is called. If the index
+            // is updated prior and the predicate panics, the element at this
+            // index would be leaked.
+            self.idx += 1;
+            if drained {
+                self.del += 1;
+                // SAFETY: We never touch this element again after returned.
+                return Some(unsafe { ptr::read(cur) });
+            } else if self.del > 0 {
+                // SAFETY: `del` > 0, so the hole slot must not overlap with current element.
+                // We use copy for move, and never touch this element again.
+                unsafe { ptr::copy_nonoverlapping(cur, p.add(i - self.del), 1) };
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.old_len - self.idx))
+    }
+}
+
+impl<V: VecLike + ?Sized, F> Drop for ExtractIf<'_, V, F> {
+    fn drop(&mut self) {
+        if self.idx < self.old_len && self.del > 0 {
+            // SAFETY: Trailing unchecked items must be valid since we never touch them.
+            unsafe {
+                let p = self.v.as_mut_ptr();
+                ptr::copy(
+                    p.add(self.idx),
+                    p.add(self.idx - self.del),
+                    self.old_len - self.idx,
+                );
+            }
+        }
+        // SAFETY: After filling holes, all items are in contiguous memory.
+        unsafe { self.v.set_len(self.old_len - self.del) };
+    }
+}
+
+/// Like `retain_mut` but awaits the decision on each element.
+///
+/// The drop guard keeps the collection consistent if the future is dropped before completion.
+#[cfg(feature = "async")]
+pub struct RetainMutAsync<'a, V: VecLike + ?Sized, F, Fut> {
+    g: BackshiftOnDrop<'a, V>,
+    f: F,
+    /// The pending decision on the element at `g.processed_len`.
+    fut: Option<Fut>,
+}
+
+#[cfg(feature = "async")]
+impl<'a, V: VecLike + ?Sized, F, Fut> RetainMutAsync<'a, V, F, Fut> {
+    pub fn new(v: &'a mut V, f: F) -> Self {
+        let original_len = v.len();
+        // Avoid double drop if the drop guard is not executed,
+        // since we may make some holes during the process.
+        unsafe { v.set_len(0) };
+        RetainMutAsync {
+            g: BackshiftOnDrop {
+                v,
+                processed_len: 0,
+                deleted_cnt: 0,
+                original_len,
+            },
+            f,
+            fut: None,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<V, F, Fut> Future for RetainMutAsync<'_, V, F, Fut>
+where
+    V: VecLike + ?Sized,
+    F: FnMut(&mut V::Item) -> Fut,
+    Fut: Future<Output = bool>,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        // SAFETY: `fut` is never moved out, and only dropped in place.
+        let this = unsafe { self.get_unchecked_mut() };
+        let g = &mut this.g;
+        loop {
+            let fut = match this.fut {
+                Some(ref mut fut) => fut,
+                None => {
+                    if g.processed_len == g.original_len {
+                        // The drop guard will set the length.
+                        return Poll::Ready(());
+                    }
+                    let p = g.v.as_mut_ptr();
+                    // SAFETY: Unchecked element must be valid.
+                    let cur = unsafe { &mut *p.add(g.processed_len) };
+                    this.fut.get_or_insert((this.f)(cur))
+                }
+            };
+            // SAFETY: `fut` is pinned since `self` is pinned.
+            let keep = match unsafe { Pin::new_unchecked(fut) }.poll(cx) {
+                Poll::Ready(keep) => keep,
+                Poll::Pending => return Poll::Pending,
+            };
+            this.fut = None;
+            let p = g.v.as_mut_ptr();
+            // SAFETY: Unchecked element must be valid.
+            let cur = unsafe { p.add(g.processed_len) };
+            if !keep {
+                // Advance early to avoid double drop if `drop_in_place` panicked.
+                g.processed_len += 1;
+                g.deleted_cnt += 1;
+                // SAFETY: We never touch this element again after dropped.
+                unsafe { ptr::drop_in_place(cur) };
+                continue;
+            }
+            if g.deleted_cnt > 0 {
+                // SAFETY: `deleted_cnt` > 0, so the hole slot must not overlap with current element.
+                // We use copy for move, and never touch this element again.
+                unsafe { ptr::copy_nonoverlapping(cur, p.add(g.processed_len - g.deleted_cnt), 1) };
+            }
+            g.processed_len += 1;
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+use core::mem;
+
+#[allow(deprecated)]
+use RetainMut;
+
+/// The heap is taken apart into a vector, retained,
+/// then rebuilt with a single `O(n)` heapify at the end.
+/// Therefore, the order in which equal elements are popped may change.
+///
+/// If the predicate of `try_retain_mut` fails, the heap is still rebuilt
+/// from the retained and the unvisited elements, so it's always left valid.
+///
+/// ```
+/// # use retain_mut::RetainMut;
+/// # use std::collections::BinaryHeap;
+/// let mut heap = BinaryHeap::from(vec!["1", "5", "x", "2", "4"]);
+/// let result = heap.try_retain_mut(|s| s.parse::<i32>().map(|x| x % 2 == 0));
+/// assert!(result.is_err());
+/// // The element that failed is still retained, and the heap is valid.
+/// assert_eq!(heap.pop(), Some("x"));
+/// ```
+#[allow(deprecated)]
+impl<T: Ord> RetainMut<T> for BinaryHeap<T> {
+    fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        retain_heap(self, |vec| RetainMut::retain_mut(vec, f));
+    }
+
+    fn try_retain_mut<F, E>(&mut self, f: F) -> Result<(), E>
+    where
+        F: FnMut(&mut T) -> Result<bool, E>,
+    {
+        retain_heap(self, |vec| RetainMut::try_retain_mut(vec, f))
+    }
+
+    fn retain_mut_count<F>(&mut self, f: F) -> usize
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        retain_heap(self, |vec| RetainMut::retain_mut_count(vec, f))
+    }
+}
+
+// This drop guard rebuilds the heap from the vector, either when all elements have
+// been processed, or when predicate failed, or when predicate or `drop` of element panicked.
+struct RebuildOnDrop<'a, T: Ord> {
+    heap: &'a mut BinaryHeap<T>,
+    vec: Vec<T>,
+}
+
+impl<T: Ord> Drop for RebuildOnDrop<'_, T> {
+    fn drop(&mut self) {
+        *self.heap = BinaryHeap::from(mem::take(&mut self.vec));
+    }
+}
+
+// Takes the heap apart into a vector for `f` to retain, and rebuilds the heap afterwards.
+fn retain_heap<T, R, F>(heap: &mut BinaryHeap<T>, f: F) -> R
+where
+    T: Ord,
+    F: FnOnce(&mut Vec<T>) -> R,
+{
+    let vec = mem::take(heap).into_vec();
+    let mut g = RebuildOnDrop { heap, vec };
+    f(&mut g.vec)
+}
@@ -0,0 +1,148 @@
+//! Extra retain methods for `BTreeMap`.
+
+use alloc::collections::btree_map::{self, BTreeMap};
+#[cfg(feature = "rayon")]
+use alloc::vec::Vec;
+use core::iter::Peekable;
+use core::mem;
+#[cfg(feature = "rayon")]
+use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
+
+use RetainMutMap;
+
+/// Trait that provides more retain methods for `BTreeMap`.
+pub trait BTreeMapExt<K, V> {
+    /// Creates an iterator which uses a closure to determine if an entry should be removed,
+    /// passing a mutable reference of the value to it.
+    ///
+    /// Entries are visited in ascending key order. If the closure returns `true`,
+    /// the entry is removed and yielded. If the closure returns `false`, the entry will
+    /// remain in the map. If the iterator is dropped before being fully consumed,
+    /// the unvisited entries are retained as well.
+    ///
+    /// This rebuilds the map rather than removing entries in place, since there is no stable
+    /// way to do that while iterating before the inherent method of Rust 1.91 below.
+    /// All the entries are moved out of the map when the iterator is created, and the retained
+    /// ones are inserted back one by one as they are visited, which takes O(n log n) time
+    /// in total. If the iterator is leaked (e.g. with `mem::forget`), the unvisited entries
+    /// are leaked with it, and the map only keeps the retained entries visited so far.
+    ///
+    /// Since Rust 1.91, `BTreeMap` has an inherent `extract_if` method taking a range as well,
+    /// which takes precedence in method call syntax.
+    ///
+    /// ```
+    /// # use retain_mut::BTreeMapExt;
+    /// # use std::collections::BTreeMap;
+    /// let mut map: BTreeMap<_, _> = (0..8).map(|x| (x, x)).collect();
+    /// let odds: Vec<_> = BTreeMapExt::extract_if(&mut map, |_, v| { *v *= 10; *v % 20 != 0 }).collect();
+    /// assert_eq!(odds, [(1, 10), (3, 30), (5, 50), (7, 70)]);
+    /// assert_eq!(map.into_iter().collect::<Vec<_>>(), [(0, 0), (2, 20), (4, 40), (6, 60)]);
+    /// ```
+    fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, K, V, F>
+    where
+        K: Ord,
+        F: FnMut(&K, &mut V) -> bool;
+
+    /// Retains only the entries specified by the predicate, passing a mutable reference
+    /// of the value to it, evaluating the predicate on the entries in parallel.
+    ///
+    /// The predicate must be `Fn` since it can be called from multiple threads at once, and
+    /// the order of evaluation is unspecified. After all the entries are decided on,
+    /// the rejected ones are removed sequentially in ascending key order.
+    /// If the predicate panics, no entry is removed.
+    ///
+    /// ```
+    /// # use retain_mut::BTreeMapExt;
+    /// # use std::collections::BTreeMap;
+    /// let mut map: BTreeMap<_, _> = (0..1000).map(|x| (x, x)).collect();
+    /// map.par_retain_mut(|_, v| { *v *= 2; *v % 3 == 0 });
+    /// assert!(map.into_iter().eq((0..1000).filter(|x| x % 3 == 0).map(|x| (x, x * 2))));
+    /// ```
+    #[cfg(feature = "rayon")]
+    fn par_retain_mut<F>(&mut self, f: F)
+    where
+        K: Ord + Sync,
+        V: Send,
+        F: Fn(&K, &mut V) -> bool + Sync;
+}
+
+/// An iterator which uses a closure to determine if an entry should be removed.
+///
+/// This struct is created by [`BTreeMapExt::extract_if`]. See its documentation for more.
+pub struct ExtractIf<'a, K: Ord, V, F> {
+    map: &'a mut BTreeMap<K, V>,
+    unvisited: Peekable<btree_map::IntoIter<K, V>>,
+    pred: F,
+}
+
+impl<K: Ord, V, F> Iterator for ExtractIf<'_, K, V, F>
+where
+    F: FnMut(&K, &mut V) -> bool,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        // Only take the entry out of `unvisited` after the predicate is called,
+        // so that if the predicate panics, it's inserted back on drop.
+        while let Some(&mut (ref k, ref mut v)) = self.unvisited.peek_mut() {
+            let extract = (self.pred)(k, v);
+            let (k, v) = self.unvisited.next().unwrap();
+            if extract {
+                return Some((k, v));
+            }
+            self.map.insert(k, v);
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.unvisited.size_hint().1)
+    }
+}
+
+impl<K: Ord, V, F> Drop for ExtractIf<'_, K, V, F> {
+    fn drop(&mut self) {
+        self.map.extend(&mut self.unvisited);
+    }
+}
+
+impl<K: Ord, V> RetainMutMap<K, V> for BTreeMap<K, V> {
+    // `BTreeMap::retain` already visits entries in ascending key order,
+    // hands out a mutable reference of the value, and keeps the tree valid
+    // if the predicate panics.
+    fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.retain(f);
+    }
+}
+
+impl<K, V> BTreeMapExt<K, V> for BTreeMap<K, V> {
+    fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, K, V, F>
+    where
+        K: Ord,
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let unvisited = mem::take(self).into_iter().peekable();
+        ExtractIf {
+            map: self,
+            unvisited,
+            pred: f,
+        }
+    }
+
+    // Both `par_iter_mut` and `retain` visit the entries in ascending key order,
+    // so the decisions can be matched up with the entries by position.
+    #[cfg(feature = "rayon")]
+    fn par_retain_mut<F>(&mut self, f: F)
+    where
+        K: Ord + Sync,
+        V: Send,
+        F: Fn(&K, &mut V) -> bool + Sync,
+    {
+        let mask: Vec<bool> = self.par_iter_mut().map(|(k, v)| f(k, v)).collect();
+        let mut mask = mask.into_iter();
+        self.retain(|_, _| mask.next().unwrap_or(true));
+    }
+}
@@ -0,0 +1,53 @@
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use core::mem;
+
+#[allow(deprecated)]
+use RetainMut;
+
+/// Mutating elements in place may break the ordering of the tree, so all elements are moved
+/// out of the set and retained, then the tree is rebuilt from the sorted kept ones.
+/// If the predicate makes some kept elements equal, only the first visited one is kept.
+#[allow(deprecated)]
+impl<T: Ord> RetainMut<T> for BTreeSet<T> {
+    fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        retain_set(self, |vec| RetainMut::retain_mut(vec, f));
+    }
+
+    fn try_retain_mut<F, E>(&mut self, f: F) -> Result<(), E>
+    where
+        F: FnMut(&mut T) -> Result<bool, E>,
+    {
+        retain_set(self, |vec| RetainMut::try_retain_mut(vec, f))
+    }
+}
+
+// This drop guard rebuilds the tree from the vector, either when all elements have
+// been processed, or when predicate or `drop` of element panicked.
+struct RebuildOnDrop<'a, T: Ord> {
+    set: &'a mut BTreeSet<T>,
+    vec: Vec<T>,
+}
+
+impl<T: Ord> Drop for RebuildOnDrop<'_, T> {
+    fn drop(&mut self) {
+        // Stable sort keeps the first visited one in front of other equal elements,
+        // which is then the one kept by `dedup`.
+        self.vec.sort();
+        self.vec.dedup();
+        *self.set = self.vec.drain(..).collect();
+    }
+}
+
+// Moves the elements of the set into a vector for `f` to retain, and rebuilds the set afterwards.
+fn retain_set<T: Ord, R, F>(set: &mut BTreeSet<T>, f: F) -> R
+where
+    F: FnOnce(&mut Vec<T>) -> R,
+{
+    let vec = mem::take(set).into_iter().collect();
+    let mut g = RebuildOnDrop { set, vec };
+    f(&mut g.vec)
+}
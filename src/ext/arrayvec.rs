@@ -0,0 +1,64 @@
+use arrayvec::ArrayVec;
+
+use backshift::{self, VecLike};
+#[allow(deprecated)]
+use RetainMut;
+
+unsafe impl<T, const CAP: usize> VecLike for ArrayVec<T, CAP> {
+    type Item = T;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.as_mut_ptr()
+    }
+
+    unsafe fn set_len(&mut self, len: usize) {
+        self.set_len(len);
+    }
+}
+
+/// This works the same way as the impl for `Vec`, in place without allocation,
+/// so it's available without the `alloc` feature.
+///
+/// ```
+/// # extern crate arrayvec;
+/// # extern crate retain_mut;
+/// # use arrayvec::ArrayVec;
+/// # use retain_mut::RetainMut;
+/// let mut vec = ArrayVec::from([1, 2, 3, 4]);
+/// RetainMut::retain_mut(&mut vec, |x| { *x *= 3; *x % 2 == 0 });
+/// assert_eq!(&vec[..], [6, 12]);
+/// ```
+///
+/// `try_retain_mut` stops right at the first error, so does `retain_mut_limit`
+/// once enough elements have been removed, leaving the rest untouched.
+///
+/// ```
+/// # extern crate arrayvec;
+/// # extern crate retain_mut;
+/// # use arrayvec::ArrayVec;
+/// # use retain_mut::RetainMut;
+/// let mut samples = ArrayVec::from([3, 120, 5, 130, 140, 7]);
+/// // Evict at most two outliers per cycle.
+/// samples.retain_mut_limit(2, |x| *x < 100);
+/// assert_eq!(&samples[..], [3, 5, 140, 7]);
+/// ```
+#[allow(deprecated)]
+impl<T, const CAP: usize> RetainMut<T> for ArrayVec<T, CAP> {
+    fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        backshift::retain_mut(self, f);
+    }
+
+    fn try_retain_mut<F, E>(&mut self, f: F) -> Result<(), E>
+    where
+        F: FnMut(&mut T) -> Result<bool, E>,
+    {
+        backshift::try_retain_mut(self, f).map(|_| ())
+    }
+}
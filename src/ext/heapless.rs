@@ -0,0 +1,51 @@
+use heapless::Vec;
+
+use backshift::{self, VecLike};
+#[allow(deprecated)]
+use RetainMut;
+
+unsafe impl<T, const N: usize> VecLike for Vec<T, N> {
+    type Item = T;
+
+    // `heapless::Vec` has no inherent `len`, it comes from the slice it dereferences to.
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.as_mut_ptr()
+    }
+
+    unsafe fn set_len(&mut self, len: usize) {
+        self.set_len(len);
+    }
+}
+
+/// This works the same way as the impl for `Vec` from `alloc`, in place without allocation,
+/// so it's available without the `alloc` feature.
+///
+/// ```
+/// # extern crate heapless;
+/// # extern crate retain_mut;
+/// # use heapless::Vec;
+/// # use retain_mut::RetainMut;
+/// let mut vec: Vec<_, 8> = Vec::from_slice(&[1, 2, 3, 4]).unwrap();
+/// RetainMut::retain_mut(&mut vec, |x| { *x *= 3; *x % 2 == 0 });
+/// assert_eq!(&vec[..], [6, 12]);
+/// ```
+#[allow(deprecated)]
+impl<T, const N: usize> RetainMut<T> for Vec<T, N> {
+    fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        backshift::retain_mut(self, f);
+    }
+
+    fn try_retain_mut<F, E>(&mut self, f: F) -> Result<(), E>
+    where
+        F: FnMut(&mut T) -> Result<bool, E>,
+    {
+        backshift::try_retain_mut(self, f).map(|_| ())
+    }
+}
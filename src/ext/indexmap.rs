@@ -0,0 +1,166 @@
+//! Extra retain methods for `IndexMap`.
+
+use indexmap::map::IndexMap;
+
+use RetainMutMap;
+
+/// Trait that provides more retain methods for `IndexMap`.
+pub trait IndexMapExt<K, V, S> {
+    /// Retains only the entries specified by the predicate, passing a mutable reference
+    /// of the value to it, like [`RetainMutMap::retain_mut`], but without preserving
+    /// the order of the retained entries.
+    ///
+    /// Each rejected entry is removed with `swap_remove_index`, which is O(1)
+    /// and moves the last entry into its place. Each entry is still visited exactly once.
+    ///
+    /// ```
+    /// # extern crate indexmap;
+    /// # extern crate retain_mut;
+    /// # use retain_mut::IndexMapExt;
+    /// # type IndexMap<K, V> = indexmap::IndexMap<K, V, std::collections::hash_map::RandomState>;
+    /// let mut map: IndexMap<_, _> = (0..6).map(|x| (x, x)).collect();
+    /// map.retain_mut_swap(|_, v| { *v *= 10; *v % 20 != 0 });
+    /// assert_eq!(map.into_iter().collect::<Vec<_>>(), [(5, 50), (1, 10), (3, 30)]);
+    /// ```
+    fn retain_mut_swap<F>(&mut self, f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool;
+
+    /// Retains only the entries specified by the predicate, passing the index,
+    /// the key and a mutable reference of the value of each entry to it.
+    ///
+    /// The index is the original position of the entry before this call,
+    /// regardless of removals before it. The order of the retained entries is preserved.
+    ///
+    /// ```
+    /// # extern crate indexmap;
+    /// # extern crate retain_mut;
+    /// # use retain_mut::IndexMapExt;
+    /// # type IndexMap<K, V> = indexmap::IndexMap<K, V, std::collections::hash_map::RandomState>;
+    /// let mut map: IndexMap<_, _> = (0..6).map(|x| (x * 10, x)).collect();
+    /// map.retain_mut_indexed(|i, _, v| { *v *= 2; i % 2 == 1 });
+    /// assert_eq!(map.into_iter().collect::<Vec<_>>(), [(10, 2), (30, 6), (50, 10)]);
+    /// ```
+    fn retain_mut_indexed<F>(&mut self, f: F)
+    where
+        F: FnMut(usize, &K, &mut V) -> bool;
+
+    /// Creates an iterator which uses a closure to determine if an entry should be removed,
+    /// passing a mutable reference of the value to it.
+    ///
+    /// If the closure returns `true`, the entry is removed and yielded.
+    /// If the closure returns `false`, the entry will remain in the map.
+    /// The entries are visited and yielded in order, and the order of the retained entries
+    /// is preserved. If the iterator is dropped before being fully consumed,
+    /// the unvisited entries are retained as well.
+    ///
+    /// The entries are visited in place by index, and each removed one is taken out with
+    /// `shift_remove_index`, so the retained entries are never moved out or rehashed.
+    /// Since each removal shifts the following entries, removing `m` entries out of `n`
+    /// takes O(n * m) time. If the iterator is leaked (e.g. with `mem::forget`),
+    /// the map is left with the retained and unvisited entries.
+    ///
+    /// Since version 2.10, `IndexMap` has an inherent `extract_if` method taking a range
+    /// as well, which takes precedence in method call syntax.
+    ///
+    /// ```
+    /// # extern crate indexmap;
+    /// # extern crate retain_mut;
+    /// # use retain_mut::IndexMapExt;
+    /// # type IndexMap<K, V> = indexmap::IndexMap<K, V, std::collections::hash_map::RandomState>;
+    /// let mut map: IndexMap<_, _> = (0..6).map(|x| (x, x)).collect();
+    /// let odds: Vec<_> = IndexMapExt::extract_if(&mut map, |_, v| { *v *= 10; *v % 20 != 0 }).collect();
+    /// assert_eq!(odds, [(1, 10), (3, 30), (5, 50)]);
+    /// assert_eq!(map.into_iter().collect::<Vec<_>>(), [(0, 0), (2, 20), (4, 40)]);
+    /// ```
+    fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, K, V, F, S>
+    where
+        F: FnMut(&K, &mut V) -> bool;
+}
+
+/// An iterator which uses a closure to determine if an entry should be removed.
+///
+/// This struct is created by [`IndexMapExt::extract_if`]. See its documentation for more.
+pub struct ExtractIf<'a, K, V, F, S> {
+    map: &'a mut IndexMap<K, V, S>,
+    /// Entries in `0..idx` are retained, and entries from `idx` are unvisited.
+    idx: usize,
+    pred: F,
+}
+
+impl<K, V, F, S> Iterator for ExtractIf<'_, K, V, F, S>
+where
+    F: FnMut(&K, &mut V) -> bool,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        // If the predicate panics, the entry stays in the map along with the unvisited ones.
+        while let Some((k, v)) = self.map.get_index_mut(self.idx) {
+            if (self.pred)(k, v) {
+                return self.map.shift_remove_index(self.idx);
+            }
+            self.idx += 1;
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.map.len() - self.idx))
+    }
+}
+
+/// The order of the retained entries is preserved,
+/// as the rejected entries are removed by shifting the following ones.
+/// See [`IndexMapExt::retain_mut_swap`] for a faster alternative without this guarantee.
+impl<K, V, S> RetainMutMap<K, V> for IndexMap<K, V, S> {
+    // `IndexMap::retain` already hands out a mutable reference of the value.
+    fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.retain(f);
+    }
+}
+
+impl<K, V, S> IndexMapExt<K, V, S> for IndexMap<K, V, S> {
+    fn retain_mut_swap<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let mut idx = 0;
+        while let Some((k, v)) = self.get_index_mut(idx) {
+            if f(k, v) {
+                idx += 1;
+            } else {
+                // The entry swapped into `idx` comes from the unchecked end,
+                // so it will be checked next.
+                self.swap_remove_index(idx);
+            }
+        }
+    }
+
+    fn retain_mut_indexed<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, &K, &mut V) -> bool,
+    {
+        // `IndexMap::retain` visits the entries in order.
+        let mut idx = 0;
+        self.retain(|k, v| {
+            let keep = f(idx, k, v);
+            idx += 1;
+            keep
+        });
+    }
+
+    fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, K, V, F, S>
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        ExtractIf {
+            map: self,
+            idx: 0,
+            pred: f,
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Extra retain methods for `IndexSet`.
+
+use core::hash::{BuildHasher, Hash};
+use core::iter::Peekable;
+use indexmap::set::{self, IndexSet};
+
+/// Trait that provides more retain methods for `IndexSet`.
+pub trait IndexSetExt<T, S> {
+    /// Creates an iterator which uses a closure to determine if an element should be removed,
+    /// passing a mutable reference to it.
+    ///
+    /// If the closure returns `true`, the element is removed and yielded.
+    /// If the closure returns `false`, the element will remain in the set.
+    /// The elements are visited and yielded in order, and the order of the retained elements
+    /// is preserved. If the iterator is dropped before being fully consumed,
+    /// the unvisited elements are retained as well.
+    ///
+    /// Unlike [`IndexMapExt::extract_if`](::IndexMapExt::extract_if), this rebuilds the set
+    /// rather than extracting in place: since the closure may mutate the elements, every
+    /// retained element has to be rehashed. All the elements are split off from the set when
+    /// the iterator is created, and the retained ones are inserted back as they are visited.
+    /// Like [`HashSetExt::extract_if`](::HashSetExt::extract_if), if the closure makes
+    /// a retained element equal to an earlier one, they are collapsed into the earlier one.
+    /// If the iterator is leaked (e.g. with `mem::forget`), the unvisited elements are
+    /// leaked as well.
+    ///
+    /// Since version 2.10, `IndexSet` has an inherent `extract_if` method taking a range
+    /// as well, which takes precedence in method call syntax.
+    ///
+    /// ```
+    /// # extern crate indexmap;
+    /// # extern crate retain_mut;
+    /// # use retain_mut::IndexSetExt;
+    /// # type IndexSet<T> = indexmap::IndexSet<T, std::collections::hash_map::RandomState>;
+    /// let mut set: IndexSet<_> = vec![5, 2, 7, 4, 1].into_iter().collect();
+    /// let odds: Vec<_> = IndexSetExt::extract_if(&mut set, |x| { *x *= 10; *x % 20 != 0 }).collect();
+    /// assert_eq!(odds, [50, 70, 10]);
+    /// assert_eq!(set.into_iter().collect::<Vec<_>>(), [20, 40]);
+    /// ```
+    fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, T, F, S>
+    where
+        T: Eq + Hash,
+        S: BuildHasher + Clone,
+        F: FnMut(&mut T) -> bool;
+}
+
+/// An iterator which uses a closure to determine if an element should be removed.
+///
+/// This struct is created by [`IndexSetExt::extract_if`]. See its documentation for more.
+pub struct ExtractIf<'a, T, F, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    set: &'a mut IndexSet<T, S>,
+    unvisited: Peekable<set::IntoIter<T>>,
+    pred: F,
+}
+
+impl<T, F, S> Iterator for ExtractIf<'_, T, F, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        // Only take the element out of `unvisited` after the predicate is called,
+        // so that if the predicate panics, it's inserted back on drop.
+        while let Some(cur) = self.unvisited.peek_mut() {
+            let extract = (self.pred)(cur);
+            let cur = self.unvisited.next().unwrap();
+            if extract {
+                return Some(cur);
+            }
+            // The retained elements are visited in order, so appending them keeps the order.
+            self.set.insert(cur);
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.unvisited.size_hint().1)
+    }
+}
+
+impl<T, F, S> Drop for ExtractIf<'_, T, F, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    fn drop(&mut self) {
+        self.set.extend(&mut self.unvisited);
+    }
+}
+
+impl<T, S> IndexSetExt<T, S> for IndexSet<T, S> {
+    fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, T, F, S>
+    where
+        T: Eq + Hash,
+        S: BuildHasher + Clone,
+        F: FnMut(&mut T) -> bool,
+    {
+        // The elements are rebuilt into the set, since they are handed out mutably.
+        // `split_off` keeps the capacity of the set for the retained elements.
+        let unvisited = self.split_off(0);
+        ExtractIf {
+            set: self,
+            unvisited: unvisited.into_iter().peekable(),
+            pred: f,
+        }
+    }
+}
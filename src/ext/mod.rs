@@ -0,0 +1,14 @@
+//! Impls for collections from other crates.
+
+#[cfg(feature = "arrayvec")]
+mod arrayvec;
+#[cfg(feature = "heapless")]
+mod heapless;
+#[cfg(feature = "indexmap")]
+pub mod indexmap;
+#[cfg(feature = "indexmap")]
+pub mod indexset;
+#[cfg(feature = "slab")]
+mod slab;
+#[cfg(feature = "smallvec")]
+pub mod smallvec;
@@ -0,0 +1,34 @@
+use slab::Slab;
+
+use RetainMutMap;
+
+/// A slab is treated as a map from the keys to the occupied entries.
+/// The entries are visited in the order of their keys,
+/// and the keys of the retained entries are stable, while the slots of
+/// the removed ones are freed for later insertions.
+///
+/// Unlike `Slab::retain`, the predicate is given the key by reference rather than by value,
+/// the same as every other `RetainMutMap` impl, so that a slab can be used in code generic
+/// over maps. A closure can still take the key by value with a `&k` pattern.
+///
+/// ```
+/// # extern crate retain_mut;
+/// # extern crate slab;
+/// # use retain_mut::RetainMutMap;
+/// # use slab::Slab;
+/// let mut slab = Slab::new();
+/// let keys: Vec<_> = (0..6).map(|x| slab.insert(x)).collect();
+/// slab.retain_mut(|&k, v| { *v *= 10; k % 2 == 0 });
+/// assert_eq!(slab.len(), 3);
+/// assert_eq!(slab[keys[2]], 20);
+/// assert!(!slab.contains(keys[3]));
+/// ```
+impl<T> RetainMutMap<usize, T> for Slab<T> {
+    // `Slab::retain` already hands out a mutable reference of the value.
+    fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&usize, &mut T) -> bool,
+    {
+        self.retain(|k, v| f(&k, v));
+    }
+}
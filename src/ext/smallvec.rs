@@ -0,0 +1,119 @@
+//! Extra retain methods for `SmallVec`.
+
+use smallvec::{Array, SmallVec};
+
+use backshift::{self, VecLike};
+#[allow(deprecated)]
+use RetainMut;
+
+/// Trait that provides more retain methods for `SmallVec`.
+pub trait SmallVecExt<A: Array> {
+    /// Creates an iterator which uses a closure to determine if an element should be removed,
+    /// passing a mutable reference to it.
+    ///
+    /// This works the same way as [`VecExt::extract_if`](::VecExt::extract_if),
+    /// no matter whether the elements are stored inline or spilled to the heap.
+    ///
+    /// ```
+    /// # extern crate retain_mut;
+    /// # extern crate smallvec;
+    /// # use retain_mut::SmallVecExt;
+    /// # use smallvec::SmallVec;
+    /// let mut vec: SmallVec<[_; 4]> = SmallVec::from_slice(&[1, 2, 3, 4, 5, 6]);
+    /// let odds: Vec<_> = vec.extract_if(|x| { *x *= 10; *x % 20 != 0 }).collect();
+    /// assert_eq!(odds, [10, 30, 50]);
+    /// assert_eq!(&vec[..], [20, 40, 60]);
+    /// ```
+    fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, A, F>
+    where
+        F: FnMut(&mut A::Item) -> bool;
+}
+
+/// An iterator which uses a closure to determine if an element should be removed.
+///
+/// This struct is created by [`SmallVecExt::extract_if`]. See its documentation for more.
+pub struct ExtractIf<'a, A, F>
+where
+    A: Array,
+    F: FnMut(&mut A::Item) -> bool,
+{
+    inner: backshift::ExtractIf<'a, SmallVec<A>, F>,
+}
+
+impl<A, F> Iterator for ExtractIf<'_, A, F>
+where
+    A: Array,
+    F: FnMut(&mut A::Item) -> bool,
+{
+    type Item = A::Item;
+
+    fn next(&mut self) -> Option<A::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<A: Array> SmallVecExt<A> for SmallVec<A> {
+    fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, A, F>
+    where
+        F: FnMut(&mut A::Item) -> bool,
+    {
+        ExtractIf {
+            inner: backshift::ExtractIf::new(self, f),
+        }
+    }
+}
+
+unsafe impl<A: Array> VecLike for SmallVec<A> {
+    type Item = A::Item;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut A::Item {
+        self.as_mut_ptr()
+    }
+
+    unsafe fn set_len(&mut self, len: usize) {
+        self.set_len(len);
+    }
+}
+
+/// This works the same way as the impl for `Vec`,
+/// no matter whether the elements are stored inline or spilled to the heap.
+///
+/// ```
+/// # extern crate retain_mut;
+/// # extern crate smallvec;
+/// # use retain_mut::RetainMut;
+/// # use smallvec::SmallVec;
+/// let mut vec: SmallVec<[_; 4]> = SmallVec::from_slice(&[1, 2, 3, 4]);
+/// RetainMut::retain_mut(&mut vec, |x| { *x *= 3; *x % 2 == 0 });
+/// assert_eq!(&vec[..], [6, 12]);
+///
+/// let mut vec: SmallVec<[_; 2]> = SmallVec::from_slice(&["1", "2", "x", "4"]);
+/// assert!(vec.spilled());
+/// let result = vec.try_retain_mut(|s| s.parse::<i32>().map(|x| x % 2 == 0));
+/// assert!(result.is_err());
+/// assert_eq!(&vec[..], ["2", "x", "4"]);
+/// ```
+#[allow(deprecated)]
+impl<A: Array> RetainMut<A::Item> for SmallVec<A> {
+    fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut A::Item) -> bool,
+    {
+        backshift::retain_mut(self, f);
+    }
+
+    fn try_retain_mut<F, E>(&mut self, f: F) -> Result<(), E>
+    where
+        F: FnMut(&mut A::Item) -> Result<bool, E>,
+    {
+        backshift::try_retain_mut(self, f).map(|_| ())
+    }
+}
@@ -0,0 +1,78 @@
+//! Retain operations for C strings.
+
+use std::ffi::CString;
+use std::mem;
+use std::vec::Vec;
+
+use backshift;
+
+/// Retains only the bytes of the C string specified by the predicate,
+/// passing a mutable reference of each byte to it.
+///
+/// Only the bytes before the terminating NUL are visited, and the string is rebuilt
+/// with a terminating NUL afterwards, reusing its allocation.
+///
+/// # Panics
+///
+/// Panics if the predicate sets a byte it retains to NUL. The predicate is given a copy
+/// of each byte, which is only written back after it returns, so in that case, or if
+/// the predicate panics, the byte is left as it was, and the string stays a valid C string.
+///
+/// ```
+/// # use retain_mut::ffi::retain_mut_cstring;
+/// # use std::ffi::CString;
+/// # use std::panic::{self, AssertUnwindSafe};
+/// let mut s = CString::new("a-b-c").unwrap();
+/// retain_mut_cstring(&mut s, |b| { b.make_ascii_uppercase(); *b != b'-' });
+/// assert_eq!(s.as_bytes_with_nul(), b"ABC\0");
+///
+/// let result = panic::catch_unwind(AssertUnwindSafe(|| {
+///     retain_mut_cstring(&mut s, |b| {
+///         if *b == b'B' {
+///             *b = 0;
+///             panic!();
+///         }
+///         true
+///     })
+/// }));
+/// assert!(result.is_err());
+/// assert!(!s.as_bytes().contains(&0));
+/// assert_eq!(s.as_bytes(), b"ABC");
+/// ```
+pub fn retain_mut_cstring<F>(s: &mut CString, mut f: F)
+where
+    F: FnMut(&mut u8) -> bool,
+{
+    let bytes = mem::take(s).into_bytes();
+    let mut g = RebuildOnDrop { s, bytes };
+    backshift::retain_mut(&mut g.bytes, |b| {
+        // The predicate works on a copy, so that a NUL it wrote before panicking
+        // never reaches the buffer.
+        let mut t = *b;
+        let keep = f(&mut t);
+        if keep {
+            assert!(
+                t != 0,
+                "predicate of retain_mut_cstring set a retained byte to NUL"
+            );
+            *b = t;
+        }
+        keep
+    });
+}
+
+// This drop guard rebuilds the C string, either when all bytes have been processed,
+// or when predicate panicked.
+struct RebuildOnDrop<'a> {
+    s: &'a mut CString,
+    bytes: Vec<u8>,
+}
+
+impl Drop for RebuildOnDrop<'_> {
+    fn drop(&mut self) {
+        let bytes = mem::take(&mut self.bytes);
+        // SAFETY: The bytes come from a `CString`, and a retained byte is only ever
+        // overwritten with a checked non-NUL byte.
+        *self.s = unsafe { CString::from_vec_unchecked(bytes) };
+    }
+}
@@ -0,0 +1,424 @@
+//! Extra retain methods for `HashMap`.
+
+use alloc::vec::{self, Vec};
+#[cfg(feature = "async")]
+use core::future::Future;
+use core::hash::{BuildHasher, Hash};
+use core::iter::Peekable;
+#[cfg(feature = "rayon")]
+use core::mem;
+#[cfg(feature = "async")]
+use core::pin::Pin;
+#[cfg(feature = "async")]
+use core::task::{Context, Poll};
+#[cfg(feature = "rayon")]
+use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
+use std::collections::HashMap;
+
+use RetainMutMap;
+
+/// Trait that provides more retain methods for `HashMap`.
+pub trait HashMapExt<K, V, S> {
+    /// Creates an iterator which uses a closure to determine if an entry should be removed,
+    /// passing a mutable reference of the value to it.
+    ///
+    /// If the closure returns `true`, the entry is removed and yielded.
+    /// If the closure returns `false`, the entry will remain in the map.
+    /// If the iterator is dropped before being fully consumed,
+    /// the unvisited entries are retained as well.
+    ///
+    /// This is not an in-place removal, since `std` doesn't expose one before Rust 1.88.
+    /// All the entries are drained into a temporary vector of the same length when
+    /// the iterator is created, and the retained ones are rehashed and inserted back into
+    /// the map as they are visited. If the iterator is leaked (e.g. with `mem::forget`),
+    /// the unvisited entries are leaked with it, and the map only keeps the retained entries
+    /// visited so far.
+    ///
+    /// Since Rust 1.88, `HashMap` has an inherent `extract_if` method,
+    /// which takes precedence in method call syntax.
+    ///
+    /// ```
+    /// # use retain_mut::HashMapExt;
+    /// # use std::collections::HashMap;
+    /// let mut map: HashMap<_, _> = (0..8).map(|x| (x, x)).collect();
+    /// let mut odds: Vec<_> = HashMapExt::extract_if(&mut map, |_, v| { *v *= 10; *v % 20 != 0 }).collect();
+    /// odds.sort();
+    /// assert_eq!(odds, [(1, 10), (3, 30), (5, 50), (7, 70)]);
+    /// assert_eq!(map.len(), 4);
+    /// ```
+    fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, K, V, F, S>
+    where
+        K: Eq + Hash,
+        S: BuildHasher,
+        F: FnMut(&K, &mut V) -> bool;
+
+    /// Retains only the entries specified by the predicate, passing a mutable reference
+    /// of the value to it, and returns the removed entries.
+    ///
+    /// The removed values keep any modification made by the predicate.
+    ///
+    /// ```
+    /// # use retain_mut::HashMapExt;
+    /// # use std::collections::HashMap;
+    /// let mut map: HashMap<_, _> = (0..8).map(|x| (x, x)).collect();
+    /// let mut removed = map.retain_mut_extract(|_, v| { *v *= 10; *v % 20 == 0 });
+    /// removed.sort();
+    /// assert_eq!(removed, [(1, 10), (3, 30), (5, 50), (7, 70)]);
+    /// assert_eq!(map.len(), 4);
+    /// ```
+    fn retain_mut_extract<F>(&mut self, f: F) -> Vec<(K, V)>
+    where
+        K: Eq + Hash,
+        S: BuildHasher,
+        F: FnMut(&K, &mut V) -> bool;
+
+    /// Retains only the entries specified by the predicate, passing a mutable reference
+    /// of the value to it, and moves the removed entries into `dest`.
+    ///
+    /// This works like [`retain_mut_extract`](HashMapExt::retain_mut_extract),
+    /// but allows reusing an existing collection. If `dest.extend` panics,
+    /// the entries not yet handed to it are retained in the map.
+    ///
+    /// ```
+    /// # use retain_mut::HashMapExt;
+    /// # use std::collections::{BTreeMap, HashMap};
+    /// let mut map: HashMap<_, _> = (0..8).map(|x| (x, x)).collect();
+    /// let mut removed = BTreeMap::new();
+    /// map.retain_mut_into(&mut removed, |_, v| { *v *= 10; *v % 20 == 0 });
+    /// assert_eq!(removed.into_iter().collect::<Vec<_>>(), [(1, 10), (3, 30), (5, 50), (7, 70)]);
+    /// assert_eq!(map.len(), 4);
+    /// ```
+    fn retain_mut_into<F, C>(&mut self, dest: &mut C, f: F)
+    where
+        K: Eq + Hash,
+        S: BuildHasher,
+        F: FnMut(&K, &mut V) -> bool,
+        C: Extend<(K, V)>;
+
+    /// Retains only the entries specified by the fallible predicate,
+    /// passing a mutable reference of the value to it, and returns all the errors.
+    ///
+    /// This visits all the entries. Entries the predicate failed on are retained,
+    /// and the errors are returned in the order the entries are visited, which is unspecified.
+    ///
+    /// ```
+    /// # use retain_mut::HashMapExt;
+    /// # use std::collections::HashMap;
+    /// let mut map: HashMap<_, _> = vec![(1, "1"), (2, "2"), (3, "x"), (4, "4"), (5, "y")]
+    ///     .into_iter()
+    ///     .collect();
+    /// let errs = map.retain_mut_collect_errs(|_, s| s.parse::<i32>().map(|x| x % 2 == 0));
+    /// assert_eq!(errs.len(), 2);
+    /// let mut keys: Vec<_> = map.into_iter().map(|(k, _)| k).collect();
+    /// keys.sort();
+    /// assert_eq!(keys, [2, 3, 4, 5]);
+    /// ```
+    fn retain_mut_collect_errs<F, E>(&mut self, f: F) -> Vec<E>
+    where
+        F: FnMut(&K, &mut V) -> Result<bool, E>;
+
+    /// Retains only the entries specified by the asynchronous predicate,
+    /// passing a mutable reference of the value to it.
+    ///
+    /// The predicate is called on each entry in turn, and the returned future is awaited
+    /// before moving on to the next entry. If the returned future is dropped before completion,
+    /// the entries already rejected are removed, while all the others are retained.
+    ///
+    /// Like [`extract_if`](HashMapExt::extract_if), all the entries are drained into
+    /// a temporary vector of the same length when the future is created, and the retained ones
+    /// are rehashed and inserted back into the map as they are decided on.
+    /// If the returned future is leaked (e.g. with `mem::forget`), the undecided entries are
+    /// leaked with it.
+    ///
+    /// ```edition2021
+    /// # use std::collections::HashMap;
+    /// # use std::future::Future;
+    /// # use std::pin::pin;
+    /// # use std::task::{Context, Poll, Waker};
+    /// # fn block_on<F: Future>(fut: F) -> F::Output {
+    /// #     let mut fut = pin!(fut);
+    /// #     let mut cx = Context::from_waker(Waker::noop());
+    /// #     loop {
+    /// #         if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+    /// #             return output;
+    /// #         }
+    /// #     }
+    /// # }
+    /// # use retain_mut::HashMapExt;
+    /// async fn is_valid(x: i32) -> bool {
+    ///     x % 20 == 0
+    /// }
+    /// # block_on(async {
+    /// let mut map: HashMap<_, _> = (0..8).map(|x| (x, x)).collect();
+    /// map.retain_mut_async(|_, v| { *v *= 10; is_valid(*v) }).await;
+    /// let mut entries: Vec<_> = map.into_iter().collect();
+    /// entries.sort();
+    /// assert_eq!(entries, [(0, 0), (2, 20), (4, 40), (6, 60)]);
+    /// # });
+    /// ```
+    #[cfg(feature = "async")]
+    fn retain_mut_async<F, Fut>(&mut self, f: F) -> RetainMutAsync<'_, K, V, F, Fut, S>
+    where
+        K: Eq + Hash,
+        S: BuildHasher,
+        F: FnMut(&K, &mut V) -> Fut,
+        Fut: Future<Output = bool>;
+
+    /// Retains only the entries specified by the predicate, passing a mutable reference
+    /// of the value to it, evaluating the predicate on the entries in parallel.
+    ///
+    /// The predicate must be `Fn` since it can be called from multiple threads at once, and
+    /// the order of evaluation is unspecified. The entries are moved out of the map first,
+    /// and after all of them are decided on, the retained ones are inserted back sequentially.
+    /// If the predicate panics, no entry is removed.
+    ///
+    /// ```
+    /// # use retain_mut::HashMapExt;
+    /// # use std::collections::HashMap;
+    /// let mut map: HashMap<_, _> = (0..1000).map(|x| (x, x)).collect();
+    /// map.par_retain_mut(|_, v| { *v *= 2; *v % 3 == 0 });
+    /// assert_eq!(map.len(), 334);
+    /// assert!(map.iter().all(|(k, v)| *v == k * 2 && v % 3 == 0));
+    /// ```
+    #[cfg(feature = "rayon")]
+    fn par_retain_mut<F>(&mut self, f: F)
+    where
+        K: Eq + Hash + Send + Sync,
+        V: Send,
+        S: BuildHasher,
+        F: Fn(&K, &mut V) -> bool + Sync;
+}
+
+/// An iterator which uses a closure to determine if an entry should be removed.
+///
+/// This struct is created by [`HashMapExt::extract_if`]. See its documentation for more.
+pub struct ExtractIf<'a, K, V, F, S = std::collections::hash_map::RandomState>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    map: &'a mut HashMap<K, V, S>,
+    unvisited: Peekable<vec::IntoIter<(K, V)>>,
+    pred: F,
+}
+
+impl<K, V, F, S> Iterator for ExtractIf<'_, K, V, F, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+    F: FnMut(&K, &mut V) -> bool,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        // Only take the entry out of `unvisited` after the predicate is called,
+        // so that if the predicate panics, it's inserted back on drop.
+        while let Some(&mut (ref k, ref mut v)) = self.unvisited.peek_mut() {
+            let extract = (self.pred)(k, v);
+            let (k, v) = self.unvisited.next().unwrap();
+            if extract {
+                return Some((k, v));
+            }
+            self.map.insert(k, v);
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.unvisited.size_hint().1)
+    }
+}
+
+impl<K, V, F, S> Drop for ExtractIf<'_, K, V, F, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn drop(&mut self) {
+        self.map.extend(&mut self.unvisited);
+    }
+}
+
+/// A future which retains entries of a map with an asynchronous predicate.
+///
+/// This struct is created by [`HashMapExt::retain_mut_async`]. See its documentation for more.
+#[cfg(feature = "async")]
+pub struct RetainMutAsync<'a, K, V, F, Fut, S = std::collections::hash_map::RandomState>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    map: &'a mut HashMap<K, V, S>,
+    unvisited: Peekable<vec::IntoIter<(K, V)>>,
+    f: F,
+    /// The pending decision on the next entry of `unvisited`.
+    fut: Option<Fut>,
+}
+
+#[cfg(feature = "async")]
+impl<K, V, F, Fut, S> Future for RetainMutAsync<'_, K, V, F, Fut, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+    F: FnMut(&K, &mut V) -> Fut,
+    Fut: Future<Output = bool>,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        // SAFETY: `fut` is never moved out, and only dropped in place.
+        let this = unsafe { self.get_unchecked_mut() };
+        loop {
+            let fut = match this.fut {
+                Some(ref mut fut) => fut,
+                None => match this.unvisited.peek_mut() {
+                    Some(&mut (ref k, ref mut v)) => this.fut.get_or_insert((this.f)(k, v)),
+                    None => return Poll::Ready(()),
+                },
+            };
+            // SAFETY: `fut` is pinned since `self` is pinned.
+            let keep = match unsafe { Pin::new_unchecked(fut) }.poll(cx) {
+                Poll::Ready(keep) => keep,
+                Poll::Pending => return Poll::Pending,
+            };
+            this.fut = None;
+            // Only take the entry out of `unvisited` after it's decided on,
+            // so that if the predicate panics, it's inserted back on drop.
+            let (k, v) = this.unvisited.next().unwrap();
+            if keep {
+                this.map.insert(k, v);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<K, V, F, Fut, S> Drop for RetainMutAsync<'_, K, V, F, Fut, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn drop(&mut self) {
+        self.map.extend(&mut self.unvisited);
+    }
+}
+
+impl<K, V, S> RetainMutMap<K, V> for HashMap<K, V, S> {
+    // `HashMap::retain` already hands out a mutable reference of the value.
+    fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.retain(f);
+    }
+}
+
+impl<K, V, S> HashMapExt<K, V, S> for HashMap<K, V, S> {
+    fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, K, V, F, S>
+    where
+        K: Eq + Hash,
+        S: BuildHasher,
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let unvisited: Vec<_> = self.drain().collect();
+        ExtractIf {
+            map: self,
+            unvisited: unvisited.into_iter().peekable(),
+            pred: f,
+        }
+    }
+
+    fn retain_mut_extract<F>(&mut self, mut f: F) -> Vec<(K, V)>
+    where
+        K: Eq + Hash,
+        S: BuildHasher,
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        HashMapExt::extract_if(self, |k, v| !f(k, v)).collect()
+    }
+
+    fn retain_mut_into<F, C>(&mut self, dest: &mut C, mut f: F)
+    where
+        K: Eq + Hash,
+        S: BuildHasher,
+        F: FnMut(&K, &mut V) -> bool,
+        C: Extend<(K, V)>,
+    {
+        dest.extend(HashMapExt::extract_if(self, |k, v| !f(k, v)));
+    }
+
+    fn retain_mut_collect_errs<F, E>(&mut self, mut f: F) -> Vec<E>
+    where
+        F: FnMut(&K, &mut V) -> Result<bool, E>,
+    {
+        let mut errs = Vec::new();
+        self.retain(|k, v| {
+            f(k, v).unwrap_or_else(|e| {
+                errs.push(e);
+                true
+            })
+        });
+        errs
+    }
+
+    #[cfg(feature = "async")]
+    fn retain_mut_async<F, Fut>(&mut self, f: F) -> RetainMutAsync<'_, K, V, F, Fut, S>
+    where
+        K: Eq + Hash,
+        S: BuildHasher,
+        F: FnMut(&K, &mut V) -> Fut,
+        Fut: Future<Output = bool>,
+    {
+        let unvisited: Vec<_> = self.drain().collect();
+        RetainMutAsync {
+            map: self,
+            unvisited: unvisited.into_iter().peekable(),
+            f,
+            fut: None,
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    fn par_retain_mut<F>(&mut self, f: F)
+    where
+        K: Eq + Hash + Send + Sync,
+        V: Send,
+        S: BuildHasher,
+        F: Fn(&K, &mut V) -> bool + Sync,
+    {
+        let entries = self.drain().collect();
+        let mut g = ExtendOnDrop { map: self, entries };
+        let mask: Vec<bool> = g
+            .entries
+            .par_iter_mut()
+            .map(|&mut (ref k, ref mut v)| f(k, v))
+            .collect();
+        let entries = mem::take(&mut g.entries);
+        let mut mask = mask.into_iter();
+        g.map
+            .extend(entries.into_iter().filter(|_| mask.next().unwrap_or(true)));
+    }
+}
+
+// This drop guard will be invoked when the entries are inserted back, or when predicate
+// panicked. It inserts the entries still held back into the map.
+#[cfg(feature = "rayon")]
+struct ExtendOnDrop<'a, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    map: &'a mut HashMap<K, V, S>,
+    entries: Vec<(K, V)>,
+}
+
+#[cfg(feature = "rayon")]
+impl<K, V, S> Drop for ExtendOnDrop<'_, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn drop(&mut self) {
+        self.map.extend(mem::take(&mut self.entries));
+    }
+}
@@ -0,0 +1,166 @@
+//! Extra retain methods for `HashSet`.
+
+use alloc::vec::{self, Vec};
+use core::iter::Peekable;
+use std::collections::HashSet;
+use std::hash::{BuildHasher, Hash};
+
+#[allow(deprecated)]
+use RetainMut;
+
+/// Trait that provides more retain methods for `HashSet`.
+pub trait HashSetExt<T, S> {
+    /// Creates an iterator which uses a closure to determine if an element should be removed,
+    /// passing a mutable reference to it.
+    ///
+    /// If the closure returns `true`, the element is removed and yielded.
+    /// If the closure returns `false`, the element will remain in the set.
+    /// If the iterator is dropped before being fully consumed,
+    /// the unvisited elements are retained as well.
+    ///
+    /// Like [`RetainMut::retain_mut`], all the elements are drained out of the set when
+    /// the iterator is created, so that they can be mutated. The retained ones are
+    /// re-inserted as they are visited, and the unvisited ones when the iterator is dropped,
+    /// which costs rehashing all of them.
+    ///
+    /// Since Rust 1.88, `HashSet` has an inherent `extract_if` method,
+    /// which takes precedence in method call syntax.
+    ///
+    /// ```
+    /// # use retain_mut::HashSetExt;
+    /// # use std::collections::HashSet;
+    /// let mut set: HashSet<_> = (0..8).collect();
+    /// let mut odds: Vec<_> = HashSetExt::extract_if(&mut set, |x| { *x *= 10; *x % 20 != 0 }).collect();
+    /// odds.sort();
+    /// assert_eq!(odds, [10, 30, 50, 70]);
+    /// assert!(set.contains(&20));
+    /// ```
+    fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, T, F, S>
+    where
+        T: Eq + Hash,
+        S: BuildHasher,
+        F: FnMut(&mut T) -> bool;
+}
+
+/// An iterator which uses a closure to determine if an element should be removed.
+///
+/// This struct is created by [`HashSetExt::extract_if`]. See its documentation for more.
+pub struct ExtractIf<'a, T, F, S = std::collections::hash_map::RandomState>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    set: &'a mut HashSet<T, S>,
+    unvisited: Peekable<vec::IntoIter<T>>,
+    pred: F,
+}
+
+impl<T, F, S> Iterator for ExtractIf<'_, T, F, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        // Only take the element out of `unvisited` after the predicate is called,
+        // so that if the predicate panics, it's inserted back on drop.
+        while let Some(cur) = self.unvisited.peek_mut() {
+            let extract = (self.pred)(cur);
+            let cur = self.unvisited.next().unwrap();
+            if extract {
+                return Some(cur);
+            }
+            self.set.insert(cur);
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.unvisited.size_hint().1)
+    }
+}
+
+impl<T, F, S> Drop for ExtractIf<'_, T, F, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    fn drop(&mut self) {
+        self.set.extend(&mut self.unvisited);
+    }
+}
+
+/// Mutating elements in place may change their hashes, so all elements are drained
+/// out of the set and retained, then the kept ones are re-inserted into the emptied table.
+/// If the predicate makes some kept elements equal, only the first visited one is kept,
+/// and the others are not counted as removed by `retain_mut_count`.
+#[allow(deprecated)]
+impl<T, S> RetainMut<T> for HashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        retain_set(self, |vec| RetainMut::retain_mut(vec, f));
+    }
+
+    fn try_retain_mut<F, E>(&mut self, f: F) -> Result<(), E>
+    where
+        F: FnMut(&mut T) -> Result<bool, E>,
+    {
+        retain_set(self, |vec| RetainMut::try_retain_mut(vec, f))
+    }
+
+    fn retain_mut_count<F>(&mut self, f: F) -> usize
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        retain_set(self, |vec| RetainMut::retain_mut_count(vec, f))
+    }
+}
+
+impl<T, S> HashSetExt<T, S> for HashSet<T, S> {
+    fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, T, F, S>
+    where
+        T: Eq + Hash,
+        S: BuildHasher,
+        F: FnMut(&mut T) -> bool,
+    {
+        let unvisited: Vec<_> = self.drain().collect();
+        ExtractIf {
+            set: self,
+            unvisited: unvisited.into_iter().peekable(),
+            pred: f,
+        }
+    }
+}
+
+// This drop guard re-inserts the elements into the set, either when all elements
+// have been processed, or when predicate or `drop` of element panicked.
+struct InsertOnDrop<'a, T: Eq + Hash, S: BuildHasher> {
+    set: &'a mut HashSet<T, S>,
+    vec: Vec<T>,
+}
+
+impl<T: Eq + Hash, S: BuildHasher> Drop for InsertOnDrop<'_, T, S> {
+    fn drop(&mut self) {
+        self.set.extend(self.vec.drain(..));
+    }
+}
+
+// Drains the set into a vector for `f` to retain, and re-inserts the elements afterwards.
+fn retain_set<T, S, R, F>(set: &mut HashSet<T, S>, f: F) -> R
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+    F: FnOnce(&mut Vec<T>) -> R,
+{
+    let vec = set.drain().collect();
+    let mut g = InsertOnDrop { set, vec };
+    f(&mut g.vec)
+}
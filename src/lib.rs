@@ -4,7 +4,8 @@
 //! This crate is no longer maintained.**
 //!
 //! This crate provides trait `RetainMut` which
-//! provides `retain_mut` method for `Vec` and `VecDeque`.
+//! provides `retain_mut` method for `Vec`, `VecDeque` and other collections,
+//! and trait `RetainMutMap` which provides the same for `HashMap` and `BTreeMap`.
 //!
 //! `retain_mut` is basically the same as `retain` except that
 //! it gives mutable reference of items to the predicate function.
@@ -27,29 +28,201 @@
 //! ### `Vec`
 //!
 //! ```
+//! # #[cfg(feature = "alloc")] {
 //! # use retain_mut::RetainMut;
 //! let mut vec = vec![1, 2, 3, 4];
 //! vec.retain_mut(|x| { *x *= 3; *x % 2 == 0 });
 //! assert_eq!(vec, [6, 12]);
+//! # }
 //! ```
 //!
 //! ### `VecDeque`
 //!
 //! ```
+//! # #[cfg(feature = "alloc")] {
 //! # use retain_mut::RetainMut;
 //! # use std::collections::VecDeque;
 //! let mut deque = VecDeque::from(vec![1, 2, 3, 4]);
 //! deque.retain_mut(|x| { *x *= 3; *x % 2 == 0 });
 //! assert_eq!(deque, [6, 12]);
+//! # }
 //! ```
+//!
+//! ### `LinkedList`
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! # use retain_mut::RetainMut;
+//! # use std::collections::LinkedList;
+//! let mut list: LinkedList<_> = (1..=4).collect();
+//! list.retain_mut(|x| { *x *= 3; *x % 2 == 0 });
+//! assert_eq!(list.into_iter().collect::<Vec<_>>(), [6, 12]);
+//! # }
+//! ```
+//!
+//! ### `BinaryHeap`
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! # use retain_mut::RetainMut;
+//! # use std::collections::BinaryHeap;
+//! let mut heap = BinaryHeap::from(vec![1, 2, 3, 4]);
+//! heap.retain_mut(|x| { *x *= 3; *x % 2 == 0 });
+//! assert_eq!(heap.into_sorted_vec(), [6, 12]);
+//! # }
+//! ```
+//!
+//! ### `HashSet`
+//!
+//! Kept elements are re-inserted after mutation, so the set stays consistent.
+//!
+//! ```
+//! # #[cfg(feature = "std")] {
+//! # use retain_mut::RetainMut;
+//! # use std::collections::HashSet;
+//! let mut set: HashSet<_> = (1..=4).collect();
+//! set.retain_mut(|x| { *x *= 3; *x % 2 == 0 });
+//! assert!(set.contains(&6) && set.contains(&12) && set.len() == 2);
+//! # }
+//! ```
+//!
+//! ### `BTreeSet`
+//!
+//! Similarly, the tree is rebuilt after mutation, so the ordering stays correct.
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! # use retain_mut::RetainMut;
+//! # use std::collections::BTreeSet;
+//! let mut set: BTreeSet<_> = (1..=4).collect();
+//! set.retain_mut(|x| { *x = 10 - *x * 3; *x % 2 == 0 });
+//! assert_eq!(set.into_iter().collect::<Vec<_>>(), [-2, 4]);
+//! # }
+//! ```
+//!
+//! ### `String`
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! # use retain_mut::RetainMut;
+//! let mut s = String::from("a\u{7}b🦀c");
+//! s.retain_mut(|c| match *c {
+//!     'b' => { *c = '🐟'; true }
+//!     '🦀' => { *c = 'x'; true }
+//!     _ => !c.is_control(),
+//! });
+//! assert_eq!(s, "a🐟xc");
+//! # }
+//! ```
+//!
+//! ## Cargo features
+//!
+//! * `alloc` (default): impls for collections from the `alloc` crate.
+//!   Before 0.2.0 the `Vec` and `VecDeque` impls were always available, so users building
+//!   with `default-features = false` need to enable this feature to keep them.
+//! * `std`: impls for collections only available in `std`, i.e. `HashMap` and `HashSet`,
+//!   and `ffi::retain_mut_cstring` for `CString`.
+//! * `async`: asynchronous retain methods, e.g. `VecExt::retain_mut_async`.
+//! * `unicode`: retain methods over grapheme clusters, e.g. `StringExt::retain_mut_graphemes`,
+//!   using the [`unicode-segmentation`](https://docs.rs/unicode-segmentation) crate.
+//! * `arrayvec`: impl for `ArrayVec` from the [`arrayvec`](https://docs.rs/arrayvec) crate,
+//!   which doesn't require `alloc`.
+//! * `heapless`: impl for `Vec` from the [`heapless`](https://docs.rs/heapless) crate,
+//!   which doesn't require `alloc` either.
+//! * `indexmap`: impls for `IndexMap` and `IndexSet` from the [`indexmap`](https://docs.rs/indexmap) crate.
+//! * `rayon`: parallel retain methods, e.g. `VecExt::par_retain_mut`,
+//!   using the [`rayon`](https://docs.rs/rayon) crate.
+//! * `slab`: impl for `Slab` from the [`slab`](https://docs.rs/slab) crate.
+//! * `smallvec`: impl for `SmallVec` from the [`smallvec`](https://docs.rs/smallvec) crate.
 
 #![no_std]
 
+#[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "arrayvec")]
+extern crate arrayvec;
+#[cfg(feature = "heapless")]
+extern crate heapless;
+#[cfg(feature = "indexmap")]
+extern crate indexmap;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "slab")]
+extern crate slab;
+#[cfg(feature = "smallvec")]
+extern crate smallvec;
+#[cfg(feature = "unicode")]
+extern crate unicode_segmentation;
+
+#[macro_use]
+mod macros;
+
+#[cfg(any(
+    feature = "alloc",
+    feature = "arrayvec",
+    feature = "heapless",
+    feature = "smallvec"
+))]
+mod backshift;
+#[cfg(feature = "alloc")]
+mod binary_heap;
+#[cfg(feature = "alloc")]
+pub mod btree_map;
+#[cfg(feature = "alloc")]
+mod btree_set;
+#[cfg(feature = "std")]
+pub mod ffi;
+#[cfg(feature = "std")]
+pub mod hash_map;
+#[cfg(feature = "std")]
+pub mod hash_set;
+#[cfg(feature = "alloc")]
+pub mod linked_list;
+#[cfg(feature = "alloc")]
+mod range;
+pub mod slice;
+#[cfg(feature = "alloc")]
+pub mod string;
+#[cfg(feature = "alloc")]
+pub mod vec;
+#[cfg(feature = "alloc")]
+pub mod vec_deque;
 
-use alloc::collections::vec_deque::VecDeque;
-use alloc::vec::Vec;
-use core::ptr;
+pub mod ext;
+
+#[cfg(feature = "alloc")]
+pub use btree_map::BTreeMapExt;
+#[cfg(feature = "indexmap")]
+pub use ext::indexmap::IndexMapExt;
+#[cfg(feature = "indexmap")]
+pub use ext::indexset::IndexSetExt;
+#[cfg(feature = "smallvec")]
+pub use ext::smallvec::SmallVecExt;
+#[cfg(feature = "std")]
+pub use hash_map::HashMapExt;
+#[cfg(feature = "std")]
+pub use hash_set::HashSetExt;
+#[cfg(feature = "alloc")]
+pub use linked_list::LinkedListExt;
+#[cfg(feature = "alloc")]
+pub use string::StringExt;
+#[cfg(feature = "alloc")]
+pub use vec::{RetainMutIter, VecExt};
+#[cfg(feature = "alloc")]
+pub use vec_deque::VecDequeExt;
+
+use core::ops::ControlFlow;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use core::ops::{ControlFlow, FnMut};
+    pub use core::option::Option;
+    pub use core::primitive::{bool, usize};
+    pub use core::result::Result;
+}
 
 /// Trait that provides `retain_mut` method.
 #[deprecated = "Rust 1.61 has included retain_mut directly"]
@@ -62,146 +235,511 @@ pub trait RetainMut<T> {
     fn retain_mut<F>(&mut self, f: F)
     where
         F: FnMut(&mut T) -> bool;
-}
 
-impl<T> RetainMut<T> for Vec<T> {
-    // The implementation is based on
-    // https://github.com/rust-lang/rust/blob/03c8ffaacb040a8753ef8e1accea701bc9f5be85/library/alloc/src/vec/mod.rs#L1478-L1569
-    fn retain_mut<F>(&mut self, mut f: F)
+    /// Retains only the elements specified by the fallible predicate,
+    /// passing a mutable reference to it.
+    ///
+    /// This works like [`retain_mut`](RetainMut::retain_mut), except that if the predicate
+    /// returns `Err`, it stops immediately and returns the error. Decisions already made stand,
+    /// while the element the predicate failed on and the unvisited ones are all retained.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use retain_mut::RetainMut;
+    /// let mut vec = vec!["1", "2", "x", "4"];
+    /// let result = vec.try_retain_mut(|s| s.parse::<i32>().map(|x| x % 2 == 0));
+    /// assert!(result.is_err());
+    /// assert_eq!(vec, ["2", "x", "4"]);
+    /// # }
+    /// ```
+    fn try_retain_mut<F, E>(&mut self, mut f: F) -> Result<(), E>
     where
-        F: FnMut(&mut T) -> bool,
+        F: FnMut(&mut T) -> Result<bool, E>,
     {
-        let original_len = self.len();
-        // Avoid double drop if the drop guard is not executed,
-        // since we may make some holes during the process.
-        unsafe { self.set_len(0) };
-
-        // Vec: [Kept, Kept, Hole, Hole, Hole, Hole, Unchecked, Unchecked]
-        //      |<-              processed len   ->| ^- next to check
-        //                  |<-  deleted cnt     ->|
-        //      |<-              original_len                          ->|
-        // Kept: Elements which predicate returns true on.
-        // Hole: Moved or dropped element slot.
-        // Unchecked: Unchecked valid elements.
-        //
-        // This drop guard will be invoked when predicate or `drop` of element panicked.
-        // It shifts unchecked elements to cover holes and `set_len` to the correct length.
-        // In cases when predicate and `drop` never panick, it will be optimized out.
-        struct BackshiftOnDrop<'a, T> {
-            v: &'a mut Vec<T>,
-            processed_len: usize,
-            deleted_cnt: usize,
-            original_len: usize,
-        }
-
-        impl<T> Drop for BackshiftOnDrop<'_, T> {
-            fn drop(&mut self) {
-                if self.deleted_cnt > 0 {
-                    // SAFETY: Trailing unchecked items must be valid since we never touch them.
-                    unsafe {
-                        ptr::copy(
-                            self.v.as_ptr().add(self.processed_len),
-                            self.v
-                                .as_mut_ptr()
-                                .add(self.processed_len - self.deleted_cnt),
-                            self.original_len - self.processed_len,
-                        );
-                    }
-                }
-                // SAFETY: After filling holes, all items are in contiguous memory.
-                unsafe {
-                    self.v.set_len(self.original_len - self.deleted_cnt);
-                }
+        let mut result = Ok(());
+        self.retain_mut(|x| {
+            if result.is_err() {
+                return true;
             }
-        }
-
-        let mut g = BackshiftOnDrop {
-            v: self,
-            processed_len: 0,
-            deleted_cnt: 0,
-            original_len,
-        };
-
-        fn process_loop<F, T, const DELETED: bool>(
-            original_len: usize,
-            f: &mut F,
-            g: &mut BackshiftOnDrop<'_, T>,
-        ) where
-            F: FnMut(&mut T) -> bool,
-        {
-            while g.processed_len != original_len {
-                // SAFETY: Unchecked element must be valid.
-                let cur = unsafe { &mut *g.v.as_mut_ptr().add(g.processed_len) };
-                if !f(cur) {
-                    // Advance early to avoid double drop if `drop_in_place` panicked.
-                    g.processed_len += 1;
-                    g.deleted_cnt += 1;
-                    // SAFETY: We never touch this element again after dropped.
-                    unsafe { ptr::drop_in_place(cur) };
-                    // We already advanced the counter.
-                    if DELETED {
-                        continue;
-                    } else {
-                        break;
-                    }
-                }
-                if DELETED {
-                    // SAFETY: `deleted_cnt` > 0, so the hole slot must not overlap with current element.
-                    // We use copy for move, and never touch this element again.
-                    unsafe {
-                        let hole_slot = g.v.as_mut_ptr().add(g.processed_len - g.deleted_cnt);
-                        ptr::copy_nonoverlapping(cur, hole_slot, 1);
-                    }
-                }
-                g.processed_len += 1;
+            f(x).unwrap_or_else(|e| {
+                result = Err(e);
+                true
+            })
+        });
+        result
+    }
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it,
+    /// and returns the number of removed elements.
+    ///
+    /// This works like [`retain_mut`](RetainMut::retain_mut) otherwise.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use retain_mut::RetainMut;
+    /// let mut vec = vec![1, 2, 3, 4];
+    /// assert_eq!(vec.retain_mut_count(|x| { *x *= 3; *x % 2 == 0 }), 2);
+    /// assert_eq!(vec, [6, 12]);
+    /// # }
+    /// ```
+    fn retain_mut_count<F>(&mut self, mut f: F) -> usize
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let mut removed = 0;
+        self.retain_mut(|x| {
+            let keep = f(x);
+            if !keep {
+                removed += 1;
             }
-        }
+            keep
+        });
+        removed
+    }
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it,
+    /// and returns whether any element was removed.
+    ///
+    /// This works like [`retain_mut`](RetainMut::retain_mut) otherwise.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use retain_mut::RetainMut;
+    /// let mut vec = vec![1, 2, 3, 4];
+    /// assert!(!vec.retain_mut_changed(|x| { *x *= 2; *x % 2 == 0 }));
+    /// assert!(vec.retain_mut_changed(|x| *x > 2));
+    /// assert_eq!(vec, [4, 6, 8]);
+    /// # }
+    /// ```
+    fn retain_mut_changed<F>(&mut self, f: F) -> bool
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        self.retain_mut_count(f) > 0
+    }
+
+    /// Retains only the elements specified by the predicate, passing the index
+    /// and a mutable reference of each element to it.
+    ///
+    /// The index counts the elements in the visiting order, so for sequences like `Vec`,
+    /// it's the original position of the element, regardless of removals before it.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use retain_mut::RetainMut;
+    /// let mut vec = vec![1, 2, 3, 4, 5];
+    /// vec.retain_mut_indexed(|i, x| { *x *= 10; i % 2 == 0 });
+    /// assert_eq!(vec, [10, 30, 50]);
+    /// # }
+    /// ```
+    fn retain_mut_indexed<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, &mut T) -> bool,
+    {
+        let mut idx = 0;
+        self.retain_mut(|x| {
+            let keep = f(idx, x);
+            idx += 1;
+            keep
+        });
+    }
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference
+    /// of a state and each element to it, and returns the final state.
+    ///
+    /// The predicate can update the state while deciding on each element,
+    /// in the order the elements are visited.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use retain_mut::RetainMut;
+    /// let mut vec = vec![3, 1, 4, 1, 5, 9, 2];
+    /// // Keep elements until the budget is exceeded.
+    /// let total = vec.retain_mut_scan(0, |total, x| {
+    ///     *total += *x;
+    ///     *total <= 10
+    /// });
+    /// assert_eq!(total, 25);
+    /// assert_eq!(vec, [3, 1, 4, 1]);
+    /// # }
+    /// ```
+    fn retain_mut_scan<St, F>(&mut self, init: St, mut f: F) -> St
+    where
+        F: FnMut(&mut St, &mut T) -> bool,
+    {
+        let mut state = init;
+        self.retain_mut(|x| f(&mut state, x));
+        state
+    }
 
-        // Stage 1: Nothing was deleted.
-        process_loop::<F, T, false>(original_len, &mut f, &mut g);
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it,
+    /// with the ability to stop early.
+    ///
+    /// The predicate returns `ControlFlow::Continue(keep)` to decide on the element and
+    /// move on, or `ControlFlow::Break(value)` to stop immediately. In the latter case,
+    /// the current element and all the unvisited ones are retained, and `Some(value)`
+    /// is returned. If all the elements are visited, `None` is returned.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use retain_mut::RetainMut;
+    /// use std::ops::ControlFlow;
+    /// let mut vec = vec![1, 2, 3, 10, 4, 5];
+    /// let stopped_at = vec.retain_mut_ctrl(|x| {
+    ///     if *x >= 10 {
+    ///         return ControlFlow::Break(*x);
+    ///     }
+    ///     *x *= 2;
+    ///     ControlFlow::Continue(*x != 4)
+    /// });
+    /// assert_eq!(stopped_at, Some(10));
+    /// assert_eq!(vec, [2, 6, 10, 4, 5]);
+    /// # }
+    /// ```
+    ///
+    /// This is useful for time-ordered queues, where no entry needs to be checked
+    /// after the first one which hasn't expired.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use retain_mut::RetainMut;
+    /// # use std::collections::VecDeque;
+    /// use std::ops::ControlFlow;
+    /// let mut deque = VecDeque::from(vec![(1, "a"), (2, "b"), (3, "c"), (7, "d"), (8, "e")]);
+    /// let now = 5;
+    /// let mut visited = 0;
+    /// deque.retain_mut_ctrl(|entry| {
+    ///     visited += 1;
+    ///     if entry.0 > now {
+    ///         return ControlFlow::Break(());
+    ///     }
+    ///     // Keep pinned entries.
+    ///     ControlFlow::Continue(entry.1 == "b")
+    /// });
+    /// assert_eq!(visited, 4);
+    /// assert_eq!(deque, [(2, "b"), (7, "d"), (8, "e")]);
+    /// # }
+    /// ```
+    fn retain_mut_ctrl<B, F>(&mut self, mut f: F) -> Option<B>
+    where
+        F: FnMut(&mut T) -> ControlFlow<B, bool>,
+    {
+        self.try_retain_mut(|x| match f(x) {
+            ControlFlow::Continue(keep) => Ok(keep),
+            ControlFlow::Break(b) => Err(b),
+        })
+        .err()
+    }
 
-        // Stage 2: Some elements were deleted.
-        process_loop::<F, T, true>(original_len, &mut f, &mut g);
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it,
+    /// but removes at most `max_removals` elements.
+    ///
+    /// Once `max_removals` elements have been removed, the predicate is no longer invoked,
+    /// and all the remaining elements are retained.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use retain_mut::RetainMut;
+    /// let mut vec = vec![1, 2, 3, 4, 5, 6];
+    /// vec.retain_mut_limit(2, |x| { *x *= 10; *x % 20 == 0 });
+    /// assert_eq!(vec, [20, 4, 5, 6]);
+    /// # }
+    /// ```
+    ///
+    /// This can be used to trim a large queue incrementally.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use retain_mut::RetainMut;
+    /// # use std::collections::VecDeque;
+    /// let mut deque = VecDeque::with_capacity(8);
+    /// // Make the deque wrap around its buffer.
+    /// deque.extend([0, 0, 0, 1, 2]);
+    /// deque.drain(..3);
+    /// deque.extend([3, 4, 5, 6, 7, 8]);
+    /// while deque.iter().any(|x| x % 3 == 0) {
+    ///     deque.retain_mut_limit(2, |x| *x % 3 != 0);
+    /// }
+    /// assert_eq!(deque, [1, 2, 4, 5, 7, 8]);
+    /// # }
+    /// ```
+    fn retain_mut_limit<F>(&mut self, max_removals: usize, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let mut removed = 0;
+        let _ = self.try_retain_mut(|x| {
+            if removed == max_removals {
+                return Err(());
+            }
+            let keep = f(x);
+            if !keep {
+                removed += 1;
+            }
+            Ok(keep)
+        });
+    }
 
-        // All item are processed. This can be optimized to `set_len` by LLVM.
-        drop(g);
+    /// Retains only the leading elements which satisfy the predicate,
+    /// passing a mutable reference to it.
+    ///
+    /// The predicate is invoked until it first returns `false`. That element and
+    /// all the elements after it are removed without being visited.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use retain_mut::RetainMut;
+    /// let mut vec = vec![1, 2, 3, 4, 1, 2];
+    /// let mut visited = 0;
+    /// vec.retain_mut_while(|x| { visited += 1; *x *= 10; *x < 30 });
+    /// assert_eq!(visited, 3);
+    /// assert_eq!(vec, [10, 20]);
+    /// # }
+    /// ```
+    fn retain_mut_while<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let mut keep = true;
+        self.retain_mut(|x| {
+            if keep {
+                keep = f(x);
+            }
+            keep
+        });
     }
 }
 
-impl<T> RetainMut<T> for VecDeque<T> {
-    // The implementation is based on
-    // https://github.com/rust-lang/rust/blob/3e21768a0a3fc84befd1cbe825ae6849e9941b73/library/alloc/src/collections/vec_deque/mod.rs#L2148-L2180
-    fn retain_mut<F>(&mut self, mut f: F)
+/// Retains only the elements specified by the predicate, passing a mutable reference to it.
+///
+/// This is the same as [`RetainMut::retain_mut`], but as a free function,
+/// which can be passed around without naming the trait method.
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// # use std::collections::VecDeque;
+/// fn prune<C>(c: &mut C, prune: fn(&mut C, fn(&mut i32) -> bool)) {
+///     prune(c, |x| { *x += 1; *x % 2 == 0 });
+/// }
+/// let mut vec = vec![1, 2, 3, 4];
+/// prune(&mut vec, retain_mut::retain_mut);
+/// assert_eq!(vec, [2, 4]);
+/// let mut deque = VecDeque::from(vec![5, 6, 7]);
+/// prune(&mut deque, retain_mut::retain_mut);
+/// assert_eq!(deque, [6, 8]);
+/// # }
+/// ```
+#[allow(deprecated)]
+pub fn retain_mut<C, T, F>(collection: &mut C, f: F)
+where
+    C: RetainMut<T> + ?Sized,
+    F: FnMut(&mut T) -> bool,
+{
+    collection.retain_mut(f);
+}
+
+/// Trait that provides `retain_mut` method for maps.
+///
+/// Unlike [`RetainMut`](trait.RetainMut.html), this trait is not deprecated: the `retain` of the
+/// std maps already passes values mutably, but there is no std counterpart of its fallible,
+/// counting, limited and early-stopping variants, nor a common trait over the map types.
+pub trait RetainMutMap<K, V> {
+    /// Retains only the entries specified by the predicate, passing a mutable reference
+    /// of the value to it.
+    ///
+    /// In other words, remove all entries `(k, v)` such that `f(&k, &mut v)` returns `false`.
+    /// Keys are only handed out immutably, since changing them could corrupt the map.
+    ///
+    /// ```
+    /// # #[cfg(feature = "std")] {
+    /// # use retain_mut::RetainMutMap;
+    /// # use std::collections::HashMap;
+    /// let mut map: HashMap<_, _> = (0..8).map(|x| (x, x)).collect();
+    /// RetainMutMap::retain_mut(&mut map, |_, v| { *v *= 3; *v % 2 == 0 });
+    /// assert_eq!(map.len(), 4);
+    /// assert!(map.values().all(|v| v % 6 == 0));
+    /// # }
+    /// ```
+    ///
+    /// For ordered maps like `BTreeMap`, entries are visited in ascending key order.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use retain_mut::RetainMutMap;
+    /// # use std::collections::BTreeMap;
+    /// let mut map: BTreeMap<_, _> = (0..4).map(|x| (x, x)).collect();
+    /// let mut visited = vec![];
+    /// RetainMutMap::retain_mut(&mut map, |k, v| { visited.push(*k); *v += 1; *v % 2 == 0 });
+    /// assert_eq!(visited, [0, 1, 2, 3]);
+    /// assert_eq!(map.into_iter().collect::<Vec<_>>(), [(1, 2), (3, 4)]);
+    /// # }
+    /// ```
+    fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool;
+
+    /// Retains only the entries specified by the predicate, passing a mutable reference
+    /// of the value to it, and returns the number of removed entries.
+    ///
+    /// This works like [`retain_mut`](RetainMutMap::retain_mut) otherwise.
+    ///
+    /// ```
+    /// # #[cfg(feature = "std")] {
+    /// # use retain_mut::RetainMutMap;
+    /// # use std::collections::HashMap;
+    /// let mut sessions: HashMap<_, _> = (0..8).map(|id| (id, id * 10)).collect();
+    /// let expired = sessions.retain_mut_count(|_, ttl| { *ttl -= 30; *ttl > 0 });
+    /// assert_eq!(expired, 4);
+    /// assert_eq!(sessions.len(), 4);
+    /// # }
+    /// ```
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use retain_mut::RetainMutMap;
+    /// # use std::collections::BTreeMap;
+    /// let mut events: BTreeMap<_, _> = (0..8).map(|t| (t, false)).collect();
+    /// let now = 5;
+    /// assert_eq!(events.retain_mut_count(|t, _| *t >= now), 5);
+    /// assert_eq!(events.keys().copied().collect::<Vec<_>>(), [5, 6, 7]);
+    /// # }
+    /// ```
+    fn retain_mut_count<F>(&mut self, mut f: F) -> usize
     where
-        F: FnMut(&mut T) -> bool,
+        F: FnMut(&K, &mut V) -> bool,
     {
-        let len = self.len();
-        let mut idx = 0;
-        let mut cur = 0;
+        let mut removed = 0;
+        self.retain_mut(|k, v| {
+            let keep = f(k, v);
+            if !keep {
+                removed += 1;
+            }
+            keep
+        });
+        removed
+    }
+
+    /// Retains only the entries specified by the fallible predicate,
+    /// passing a mutable reference of the value to it.
+    ///
+    /// This works like [`retain_mut`](RetainMutMap::retain_mut), except that if the predicate
+    /// returns `Err`, it stops immediately and returns the error. Decisions already made stand,
+    /// while the entry the predicate failed on and the unvisited ones are all retained.
+    ///
+    /// ```
+    /// # #[cfg(feature = "std")] {
+    /// # use retain_mut::RetainMutMap;
+    /// # use std::collections::HashMap;
+    /// let mut map: HashMap<_, _> = (0..8).map(|x| (x, x.to_string())).collect();
+    /// map.insert(8, "x".to_string());
+    /// let result = map.try_retain_mut(|_, v| v.parse::<i32>().map(|x| x % 2 == 0));
+    /// assert!(result.is_err());
+    /// assert!(map.contains_key(&8));
+    /// # }
+    /// ```
+    ///
+    /// For ordered maps like `BTreeMap`, it stops at the first failing entry in key order.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use retain_mut::RetainMutMap;
+    /// # use std::collections::BTreeMap;
+    /// let mut map: BTreeMap<_, _> = vec![(1, "1"), (2, "x"), (3, "3"), (4, "y")].into_iter().collect();
+    /// let result = map.try_retain_mut(|_, v| v.parse::<i32>().map(|x| x > 1));
+    /// assert_eq!(result.unwrap_err().to_string(), "invalid digit found in string");
+    /// assert_eq!(map.into_iter().collect::<Vec<_>>(), [(2, "x"), (3, "3"), (4, "y")]);
+    /// # }
+    /// ```
+    fn try_retain_mut<F, E>(&mut self, mut f: F) -> Result<(), E>
+    where
+        F: FnMut(&K, &mut V) -> Result<bool, E>,
+    {
+        let mut result = Ok(());
+        self.retain_mut(|k, v| {
+            if result.is_err() {
+                return true;
+            }
+            f(k, v).unwrap_or_else(|e| {
+                result = Err(e);
+                true
+            })
+        });
+        result
+    }
 
-        // Stage 1: All values are retained.
-        while cur < len {
-            if !f(&mut self[cur]) {
-                cur += 1;
-                break;
+    /// Retains only the entries specified by the predicate, passing a mutable reference
+    /// of the value to it, but removes at most `max_removals` entries.
+    ///
+    /// Once `max_removals` entries have been removed, the predicate is no longer invoked,
+    /// and all the remaining entries are retained. For maps with unspecified iteration order
+    /// like `HashMap`, which entries are visited before the cutoff is arbitrary.
+    ///
+    /// ```
+    /// # #[cfg(feature = "std")] {
+    /// # use retain_mut::RetainMutMap;
+    /// # use std::collections::HashMap;
+    /// let mut map: HashMap<_, _> = (0..8).map(|x| (x, x)).collect();
+    /// while map.values().any(|v| v % 2 == 1) {
+    ///     let len = map.len();
+    ///     map.retain_mut_limit(2, |_, v| *v % 2 == 0);
+    ///     assert!(len - map.len() <= 2);
+    /// }
+    /// assert_eq!(map.len(), 4);
+    /// # }
+    /// ```
+    fn retain_mut_limit<F>(&mut self, max_removals: usize, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let mut removed = 0;
+        let _ = self.try_retain_mut(|k, v| {
+            if removed == max_removals {
+                return Err(());
             }
-            cur += 1;
-            idx += 1;
-        }
-        // Stage 2: Swap retained value into current idx.
-        while cur < len {
-            if !f(&mut self[cur]) {
-                cur += 1;
-                continue;
+            let keep = f(k, v);
+            if !keep {
+                removed += 1;
             }
+            Ok(keep)
+        });
+    }
 
-            self.swap(idx, cur);
-            cur += 1;
-            idx += 1;
-        }
-        // Stage 3: Trancate all values after idx.
-        if cur != idx {
-            self.truncate(idx);
-        }
+    /// Retains only the entries specified by the predicate, passing a mutable reference
+    /// of the value to it, with the ability to stop early.
+    ///
+    /// The predicate returns `ControlFlow::Continue(keep)` to decide on the entry and
+    /// move on, or `ControlFlow::Break(value)` to stop immediately. In the latter case,
+    /// the current entry and all the unvisited ones are retained, and `Some(value)`
+    /// is returned. If all the entries are visited, `None` is returned. For maps with
+    /// unspecified iteration order like `HashMap`, which entries are visited before
+    /// stopping is arbitrary.
+    ///
+    /// ```
+    /// # #[cfg(feature = "std")] {
+    /// # use retain_mut::RetainMutMap;
+    /// # use std::collections::HashMap;
+    /// use std::ops::ControlFlow;
+    /// let mut map: HashMap<_, _> = (0..8).map(|x| (x, x)).collect();
+    /// let mut budget = 3;
+    /// let stopped = map.retain_mut_ctrl(|_, _| {
+    ///     if budget == 0 {
+    ///         return ControlFlow::Break("out of budget");
+    ///     }
+    ///     budget -= 1;
+    ///     ControlFlow::Continue(false)
+    /// });
+    /// assert_eq!(stopped, Some("out of budget"));
+    /// assert_eq!(map.len(), 5);
+    /// # }
+    /// ```
+    fn retain_mut_ctrl<B, F>(&mut self, mut f: F) -> Option<B>
+    where
+        F: FnMut(&K, &mut V) -> ControlFlow<B, bool>,
+    {
+        self.try_retain_mut(|k, v| match f(k, v) {
+            ControlFlow::Continue(keep) => Ok(keep),
+            ControlFlow::Break(b) => Err(b),
+        })
+        .err()
     }
 }
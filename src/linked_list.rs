@@ -0,0 +1,192 @@
+//! Extra retain methods for `LinkedList`.
+
+use alloc::collections::LinkedList;
+use core::convert::Infallible;
+use core::mem;
+
+#[allow(deprecated)]
+use RetainMut;
+
+/// Trait that provides more retain methods for `LinkedList`.
+pub trait LinkedListExt<T> {
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it,
+    /// like [`RetainMut::retain_mut`], but visiting the elements from the back to the front.
+    ///
+    /// The order of the retained elements is still preserved.
+    ///
+    /// ```
+    /// # use retain_mut::LinkedListExt;
+    /// # use std::collections::LinkedList;
+    /// let mut list: LinkedList<_> = vec![1, 5, 2, 4, 3].into_iter().collect();
+    /// let mut max = 0;
+    /// // Only keep elements which are greater than all elements after them.
+    /// list.retain_mut_rev(|x| if *x > max { max = *x; true } else { false });
+    /// assert!(list.into_iter().eq(vec![5, 4, 3]));
+    /// ```
+    fn retain_mut_rev<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool;
+
+    /// Removes all the elements for which the predicate returns `true`, passing a mutable
+    /// reference to each of them, and returns them as a new list, in their original order.
+    ///
+    /// The removed nodes are moved to the returned list as they are, so no element is
+    /// dropped or reallocated.
+    ///
+    /// If the predicate panics, the elements removed so far are dropped,
+    /// and the remaining ones are kept in the list.
+    ///
+    /// `LinkedList` has an unstable inherent `extract_if` method,
+    /// so this one is better called with the fully qualified syntax.
+    ///
+    /// ```
+    /// # use retain_mut::LinkedListExt;
+    /// # use std::collections::LinkedList;
+    /// let mut list: LinkedList<_> = (0..8).collect();
+    /// let odds = LinkedListExt::extract_if(&mut list, |x| { *x *= 10; *x % 20 != 0 });
+    /// assert!(odds.into_iter().eq([10, 30, 50, 70].iter().cloned()));
+    /// assert!(list.into_iter().eq([0, 20, 40, 60].iter().cloned()));
+    /// ```
+    fn extract_if<F>(&mut self, f: F) -> LinkedList<T>
+    where
+        F: FnMut(&mut T) -> bool;
+}
+
+impl<T> LinkedListExt<T> for LinkedList<T> {
+    // Same as `retain_list`, but nodes are detached from the back with `split_off(len - 1)`,
+    // and the kept ones are prepended to the front of the decided list.
+    fn retain_mut_rev<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let unchecked = mem::take(self);
+        let mut g = PrependOnDrop {
+            list: self,
+            unchecked,
+            kept: LinkedList::new(),
+        };
+
+        while let Some(cur) = g.unchecked.back_mut() {
+            let keep = f(cur);
+            let mut node = g.unchecked.split_off(g.unchecked.len() - 1);
+            if keep {
+                node.append(&mut g.kept);
+                g.kept = node;
+            }
+        }
+    }
+
+    fn extract_if<F>(&mut self, mut f: F) -> LinkedList<T>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let mut extracted = LinkedList::new();
+        let result = try_retain_list::<T, _, _, Infallible>(
+            self,
+            |x| Ok(!f(x)),
+            |mut node| extracted.append(&mut node),
+        );
+        match result {
+            Ok(_) => extracted,
+            Err(e) => match e {},
+        }
+    }
+}
+
+#[allow(deprecated)]
+impl<T> RetainMut<T> for LinkedList<T> {
+    fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        retain_list(self, f);
+    }
+
+    fn retain_mut_count<F>(&mut self, f: F) -> usize
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        retain_list(self, f)
+    }
+
+    // An error is handled the same way as a panic, by leaving the current node
+    // with the unchecked ones, which are appended back after the decided ones.
+    fn try_retain_mut<F, E>(&mut self, f: F) -> Result<(), E>
+    where
+        F: FnMut(&mut T) -> Result<bool, E>,
+    {
+        try_retain_list(self, f, drop).map(|_| ())
+    }
+}
+
+// This drop guard will be invoked when predicate failed, or when predicate or `drop` of
+// element panicked.
+// It moves the unchecked nodes back to the end of the list.
+struct AppendOnDrop<'a, T> {
+    list: &'a mut LinkedList<T>,
+    unchecked: LinkedList<T>,
+}
+
+impl<T> Drop for AppendOnDrop<'_, T> {
+    fn drop(&mut self) {
+        self.list.append(&mut self.unchecked);
+    }
+}
+
+// This drop guard is the reverse counterpart of `AppendOnDrop`.
+// It puts the unchecked nodes back in front of the kept ones, and moves them all into the list.
+struct PrependOnDrop<'a, T> {
+    list: &'a mut LinkedList<T>,
+    unchecked: LinkedList<T>,
+    kept: LinkedList<T>,
+}
+
+impl<T> Drop for PrependOnDrop<'_, T> {
+    fn drop(&mut self) {
+        self.unchecked.append(&mut self.kept);
+        mem::swap(self.list, &mut self.unchecked);
+    }
+}
+
+fn retain_list<T, F>(list: &mut LinkedList<T>, mut f: F) -> usize
+where
+    F: FnMut(&mut T) -> bool,
+{
+    match try_retain_list::<T, _, _, Infallible>(list, |x| Ok(f(x)), drop) {
+        Ok(removed) => removed,
+        Err(e) => match e {},
+    }
+}
+
+// `CursorMut` is not stable, so instead, nodes are detached from the front one by one
+// with `split_off(1)` and moved back with `append`. Both are O(1) and neither of them
+// reallocates the node. Each removed node is passed to `removed` as a single-element list.
+// Returns the number of removed nodes, unless predicate failed.
+fn try_retain_list<T, F, R, E>(
+    list: &mut LinkedList<T>,
+    mut f: F,
+    mut removed: R,
+) -> Result<usize, E>
+where
+    F: FnMut(&mut T) -> Result<bool, E>,
+    R: FnMut(LinkedList<T>),
+{
+    let unchecked = mem::take(list);
+    let mut g = AppendOnDrop { list, unchecked };
+    let mut count = 0;
+
+    while let Some(cur) = g.unchecked.front_mut() {
+        // Keep the element in the unchecked list until predicate returns,
+        // so that it's not lost if predicate failed or panicked.
+        let keep = f(cur)?;
+        let rest = g.unchecked.split_off(1);
+        let mut node = mem::replace(&mut g.unchecked, rest);
+        if keep {
+            g.list.append(&mut node);
+        } else {
+            removed(node);
+            count += 1;
+        }
+    }
+    Ok(count)
+}
@@ -0,0 +1,139 @@
+/// Implements [`RetainMut`](trait.RetainMut.html) for a type by delegating to a collection inside it.
+///
+/// The macro takes the type optionally preceded by `impl` with its type parameters, the type of the inner
+/// collection, and a closure-like accessor from `&mut Self` to the inner collection.
+/// The type parameters must be plain identifiers, without bounds or lifetimes.
+/// Every method of the trait is forwarded to the inner collection, so its own implementation,
+/// including the panic safety of the unsafe backshift algorithm for `Vec`, is used as is.
+///
+/// No unsafe code is generated, and the accessor must evaluate to a `&mut` of the inner
+/// collection type given. The predicate can still mutate the elements, so invariants of
+/// the wrapper type depending on the element values need to be maintained by the caller.
+///
+/// The inner collection type has to be spelled out: Rust cannot name a type in an impl header
+/// from the type of an expression, so it cannot be inferred from the accessor, and the element
+/// type of a generic impl needs a bound on it. Every path in the expansion, including `bool`
+/// and `usize`, goes through this crate, so items with those names in scope at the call site
+/// do not affect it.
+///
+/// This works in `no_std` as long as the inner collection is available.
+///
+/// ```
+/// # #[macro_use] extern crate retain_mut;
+/// # #[allow(deprecated)]
+/// use retain_mut::RetainMut;
+///
+/// /// A vector of unique elements.
+/// struct UniqueVec<T>(Vec<T>);
+/// # #[allow(dead_code, non_camel_case_types)]
+/// # type Result<T> = std::result::Result<T, ()>;
+/// # #[allow(dead_code, non_camel_case_types)]
+/// # struct usize;
+/// # #[allow(dead_code, non_camel_case_types)]
+/// # struct bool;
+///
+/// # #[cfg(feature = "alloc")]
+/// impl_retain_mut!(impl<T> UniqueVec<T>, Vec<T>, |s| &mut s.0);
+///
+/// # fn main() {
+/// # #[cfg(feature = "alloc")] {
+/// let mut v = UniqueVec(vec![1, 2, 3, 4]);
+/// v.retain_mut(|x| { *x *= 10; *x != 20 });
+/// assert_eq!(v.0, [10, 30, 40]);
+/// # }
+/// # }
+/// ```
+#[macro_export]
+macro_rules! impl_retain_mut {
+    (impl<$($g:ident),*> $ty:ty, $inner:ty, |$s:ident| $access:expr) => {
+        #[allow(deprecated)]
+        impl<$($g,)* __T> $crate::RetainMut<__T> for $ty
+        where
+            $inner: $crate::RetainMut<__T>,
+        {
+            fn retain_mut<__F>(&mut self, f: __F)
+            where
+                __F: $crate::__private::FnMut(&mut __T) -> $crate::__private::bool,
+            {
+                let $s = self;
+                let inner: &mut $inner = $access;
+                $crate::RetainMut::retain_mut(inner, f)
+            }
+
+            fn try_retain_mut<__F, __E>(&mut self, f: __F) -> $crate::__private::Result<(), __E>
+            where
+                __F: $crate::__private::FnMut(&mut __T) -> $crate::__private::Result<$crate::__private::bool, __E>,
+            {
+                let $s = self;
+                let inner: &mut $inner = $access;
+                $crate::RetainMut::try_retain_mut(inner, f)
+            }
+
+            fn retain_mut_count<__F>(&mut self, f: __F) -> $crate::__private::usize
+            where
+                __F: $crate::__private::FnMut(&mut __T) -> $crate::__private::bool,
+            {
+                let $s = self;
+                let inner: &mut $inner = $access;
+                $crate::RetainMut::retain_mut_count(inner, f)
+            }
+
+            fn retain_mut_changed<__F>(&mut self, f: __F) -> $crate::__private::bool
+            where
+                __F: $crate::__private::FnMut(&mut __T) -> $crate::__private::bool,
+            {
+                let $s = self;
+                let inner: &mut $inner = $access;
+                $crate::RetainMut::retain_mut_changed(inner, f)
+            }
+
+            fn retain_mut_indexed<__F>(&mut self, f: __F)
+            where
+                __F: $crate::__private::FnMut($crate::__private::usize, &mut __T) -> $crate::__private::bool,
+            {
+                let $s = self;
+                let inner: &mut $inner = $access;
+                $crate::RetainMut::retain_mut_indexed(inner, f)
+            }
+
+            fn retain_mut_scan<__St, __F>(&mut self, init: __St, f: __F) -> __St
+            where
+                __F: $crate::__private::FnMut(&mut __St, &mut __T) -> $crate::__private::bool,
+            {
+                let $s = self;
+                let inner: &mut $inner = $access;
+                $crate::RetainMut::retain_mut_scan(inner, init, f)
+            }
+
+            fn retain_mut_ctrl<__B, __F>(&mut self, f: __F) -> $crate::__private::Option<__B>
+            where
+                __F: $crate::__private::FnMut(&mut __T) -> $crate::__private::ControlFlow<__B, $crate::__private::bool>,
+            {
+                let $s = self;
+                let inner: &mut $inner = $access;
+                $crate::RetainMut::retain_mut_ctrl(inner, f)
+            }
+
+            fn retain_mut_limit<__F>(&mut self, max_removals: $crate::__private::usize, f: __F)
+            where
+                __F: $crate::__private::FnMut(&mut __T) -> $crate::__private::bool,
+            {
+                let $s = self;
+                let inner: &mut $inner = $access;
+                $crate::RetainMut::retain_mut_limit(inner, max_removals, f)
+            }
+
+            fn retain_mut_while<__F>(&mut self, f: __F)
+            where
+                __F: $crate::__private::FnMut(&mut __T) -> $crate::__private::bool,
+            {
+                let $s = self;
+                let inner: &mut $inner = $access;
+                $crate::RetainMut::retain_mut_while(inner, f)
+            }
+        }
+    };
+    ($ty:ty, $inner:ty, |$s:ident| $access:expr) => {
+        $crate::impl_retain_mut!(impl<> $ty, $inner, |$s| $access);
+    };
+}
@@ -0,0 +1,45 @@
+//! Retain-like operations for slices, which can't change their length.
+
+/// Partitions the slice in place with the predicate, passing a mutable reference to it,
+/// and returns the number of elements the predicate returns `true` on.
+///
+/// Those elements are moved to the front, and the others to the back, both preserving
+/// their relative order. The predicate is called on each element exactly once in the
+/// original order. This takes O(n log n) time and doesn't allocate.
+///
+/// If the predicate panics, all elements are still in the slice, but in an unspecified order.
+///
+/// ```
+/// # use retain_mut::slice::stable_partition_mut;
+/// let mut buf = [1, 2, 3, 4, 5, 6];
+/// let kept = stable_partition_mut(&mut buf, |x| { *x *= 10; *x % 20 == 0 });
+/// assert_eq!(kept, 3);
+/// assert_eq!(buf, [20, 40, 60, 10, 30, 50]);
+/// assert_eq!(&buf[..kept], [20, 40, 60]);
+/// ```
+pub fn stable_partition_mut<T, F>(slice: &mut [T], mut f: F) -> usize
+where
+    F: FnMut(&mut T) -> bool,
+{
+    partition(slice, &mut f)
+}
+
+fn partition<T, F>(slice: &mut [T], f: &mut F) -> usize
+where
+    F: FnMut(&mut T) -> bool,
+{
+    match slice.len() {
+        0 => 0,
+        1 => f(&mut slice[0]) as usize,
+        len => {
+            // Partition both halves, and then swap the rejected elements of the left half
+            // with the kept elements of the right half.
+            let mid = len / 2;
+            let (left, right) = slice.split_at_mut(mid);
+            let left_kept = partition(left, f);
+            let right_kept = partition(right, f);
+            slice[left_kept..mid + right_kept].rotate_left(mid - left_kept);
+            left_kept + right_kept
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Extra retain methods for `String`.
+
+use alloc::string::String;
+use core::convert::Infallible;
+use core::mem;
+#[cfg(feature = "unicode")]
+use unicode_segmentation::UnicodeSegmentation;
+
+#[allow(deprecated)]
+use RetainMut;
+
+/// Trait that provides more retain methods for `String`.
+pub trait StringExt {
+    /// Retains only the chars specified by the predicate, passing the byte offset of each char
+    /// in the original string and a mutable reference of it to the predicate.
+    ///
+    /// Like [`RetainMut::retain_mut`], kept chars may be mutated to ones with a different
+    /// length in UTF-8, and the string is rebuilt accordingly. The offsets passed to
+    /// the predicate are always those in the original string.
+    ///
+    /// ```
+    /// # use retain_mut::StringExt;
+    /// let mut s = String::from("ab\u{e9}cd");
+    /// // Drop the chars in bytes `2..5`, and widen the rest.
+    /// s.retain_mut_byte_indexed(|i, ch| {
+    ///     *ch = match *ch {
+    ///         'a' => '\u{e0}',
+    ///         ch => ch,
+    ///     };
+    ///     !(2..5).contains(&i)
+    /// });
+    /// assert_eq!(s, "\u{e0}bd");
+    /// ```
+    fn retain_mut_byte_indexed<F>(&mut self, f: F)
+    where
+        F: FnMut(usize, &mut char) -> bool;
+
+    /// Retains only the extended grapheme clusters specified by the predicate,
+    /// passing each cluster to it as a mutable `String`.
+    ///
+    /// Unlike [`RetainMut::retain_mut`], which works on `char`s, this never splits
+    /// combining marks or emoji sequences. The predicate may rewrite the cluster freely,
+    /// and the string is rebuilt from the kept clusters, including their modifications.
+    ///
+    /// If the predicate panics, the unvisited clusters are retained as they were.
+    ///
+    /// ```
+    /// # use retain_mut::StringExt;
+    /// let mut s = String::from("e\u{301}a\u{1F1EF}\u{1F1F5}!");
+    /// s.retain_mut_graphemes(|g| {
+    ///     if g.len() == 1 {
+    ///         g.make_ascii_uppercase();
+    ///     }
+    ///     g != "!"
+    /// });
+    /// assert_eq!(s, "e\u{301}A\u{1F1EF}\u{1F1F5}");
+    /// ```
+    #[cfg(feature = "unicode")]
+    fn retain_mut_graphemes<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut String) -> bool;
+}
+
+impl StringExt for String {
+    fn retain_mut_byte_indexed<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, &mut char) -> bool,
+    {
+        match try_retain_string::<_, Infallible>(self, |i, ch| Ok(f(i, ch))) {
+            Ok(()) => {}
+            Err(e) => match e {},
+        }
+    }
+
+    #[cfg(feature = "unicode")]
+    fn retain_mut_graphemes<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut String) -> bool,
+    {
+        let len = self.len();
+        let src = mem::replace(self, String::with_capacity(len));
+        let mut g = PushOnDrop {
+            s: self,
+            src,
+            idx: 0,
+        };
+        // The buffer handed to the predicate is reused across clusters.
+        let mut buf = String::new();
+        for cluster in g.src.graphemes(true) {
+            buf.clear();
+            buf.push_str(cluster);
+            if f(&mut buf) {
+                g.s.push_str(&buf);
+            }
+            g.idx += cluster.len();
+        }
+    }
+}
+
+/// Mutated chars may have a different length in UTF-8,
+/// so kept chars are re-encoded into a new buffer.
+///
+/// If the predicate of `try_retain_mut` fails, the string is still left with valid UTF-8.
+/// Since each char is handed to the predicate as a copy, the char it failed on is retained
+/// as it was, discarding any modification.
+///
+/// ```
+/// # use retain_mut::RetainMut;
+/// let mut s = String::from("abc-d");
+/// let result = s.try_retain_mut(|ch| match *ch {
+///     'a'..='z' => { *ch = ch.to_ascii_uppercase(); Ok(*ch != 'B') }
+///     _ => Err(*ch),
+/// });
+/// assert_eq!(result, Err('-'));
+/// assert_eq!(s, "AC-d");
+/// ```
+#[allow(deprecated)]
+impl RetainMut<char> for String {
+    fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut char) -> bool,
+    {
+        match try_retain_string::<_, Infallible>(self, |_, ch| Ok(f(ch))) {
+            Ok(()) => {}
+            Err(e) => match e {},
+        }
+    }
+
+    fn try_retain_mut<F, E>(&mut self, mut f: F) -> Result<(), E>
+    where
+        F: FnMut(&mut char) -> Result<bool, E>,
+    {
+        try_retain_string(self, |_, ch| f(ch))
+    }
+}
+
+// This drop guard will be invoked when predicate failed or panicked.
+// It appends the unchecked chars, so that the string is left with valid UTF-8.
+struct PushOnDrop<'a> {
+    s: &'a mut String,
+    src: String,
+    idx: usize,
+}
+
+impl Drop for PushOnDrop<'_> {
+    fn drop(&mut self) {
+        self.s.push_str(&self.src[self.idx..]);
+    }
+}
+
+// The predicate is given the byte offset of each char in the original string.
+fn try_retain_string<F, E>(s: &mut String, mut f: F) -> Result<(), E>
+where
+    F: FnMut(usize, &mut char) -> Result<bool, E>,
+{
+    let len = s.len();
+    let src = mem::replace(s, String::with_capacity(len));
+    let mut g = PushOnDrop { s, src, idx: 0 };
+    while let Some(mut ch) = g.src[g.idx..].chars().next() {
+        let ch_len = ch.len_utf8();
+        if f(g.idx, &mut ch)? {
+            g.s.push(ch);
+        }
+        g.idx += ch_len;
+    }
+    Ok(())
+}
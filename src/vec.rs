@@ -0,0 +1,906 @@
+//! Extra retain methods for `Vec`.
+
+use alloc::vec::Vec;
+use core::cmp;
+#[cfg(feature = "async")]
+use core::future::Future;
+use core::mem;
+use core::ops::RangeBounds;
+#[cfg(feature = "async")]
+use core::pin::Pin;
+#[cfg(feature = "async")]
+use core::task::{Context, Poll};
+#[cfg(feature = "rayon")]
+use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
+
+use backshift;
+use range;
+#[allow(deprecated)]
+use RetainMut;
+
+/// Trait that provides more retain methods for `Vec`.
+pub trait VecExt<T> {
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it,
+    /// like [`RetainMut::retain_mut`], but visiting the elements in the reverse order.
+    ///
+    /// The order of the retained elements is still preserved.
+    ///
+    /// ```
+    /// # use retain_mut::VecExt;
+    /// let mut vec = vec![1, 5, 2, 4, 3];
+    /// let mut max = 0;
+    /// // Only keep elements which are greater than all elements after them.
+    /// vec.retain_mut_rev(|x| if *x > max { max = *x; true } else { false });
+    /// assert_eq!(vec, [5, 4, 3]);
+    /// ```
+    fn retain_mut_rev<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool;
+
+    /// Creates an iterator which uses a closure to determine if an element should be removed,
+    /// passing a mutable reference to it.
+    ///
+    /// If the closure returns `true`, the element is removed and yielded.
+    /// If the closure returns `false`, the element will remain in the vector.
+    /// The retained elements keep their order, and holes are filled when the iterator
+    /// is dropped. If the iterator is dropped before being fully consumed,
+    /// the unvisited elements are retained as well.
+    ///
+    /// If the returned `ExtractIf` is leaked (e.g. with `mem::forget`), the vector may have
+    /// lost and leaked elements arbitrarily, including the unvisited ones.
+    ///
+    /// Since Rust 1.87, `Vec` has an inherent `extract_if` method taking a range as well,
+    /// which takes precedence in method call syntax.
+    ///
+    /// ```
+    /// # use retain_mut::VecExt;
+    /// let mut vec = vec![1, 2, 3, 4, 5, 6];
+    /// let odds: Vec<_> = VecExt::extract_if(&mut vec, |x| { *x *= 10; *x % 20 != 0 }).collect();
+    /// assert_eq!(odds, [10, 30, 50]);
+    /// assert_eq!(vec, [20, 40, 60]);
+    /// ```
+    fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, T, F>
+    where
+        F: FnMut(&mut T) -> bool;
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it,
+    /// and returns the removed elements in their original order.
+    ///
+    /// The removed elements keep any modification made by the predicate.
+    ///
+    /// ```
+    /// # use retain_mut::VecExt;
+    /// let mut vec = vec![1, 2, 3, 4, 5, 6];
+    /// let removed = vec.retain_mut_extract(|x| { *x *= 10; *x % 20 == 0 });
+    /// assert_eq!(vec, [20, 40, 60]);
+    /// assert_eq!(removed, [10, 30, 50]);
+    /// ```
+    fn retain_mut_extract<F>(&mut self, f: F) -> Vec<T>
+    where
+        F: FnMut(&mut T) -> bool;
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it,
+    /// and moves the removed elements into `dest` in their original order.
+    ///
+    /// This works like [`retain_mut_extract`](VecExt::retain_mut_extract),
+    /// but allows reusing an existing collection. If `dest.extend` panics,
+    /// the elements not yet handed to it are retained in the vector.
+    ///
+    /// ```
+    /// # use retain_mut::VecExt;
+    /// let mut vec = vec![1, 2, 3, 4, 5, 6];
+    /// let mut removed = vec![0];
+    /// vec.retain_mut_into(&mut removed, |x| { *x *= 10; *x % 20 == 0 });
+    /// assert_eq!(vec, [20, 40, 60]);
+    /// assert_eq!(removed, [0, 10, 30, 50]);
+    /// ```
+    fn retain_mut_into<F, C>(&mut self, dest: &mut C, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+        C: Extend<T>;
+
+    /// Retains only the elements in `range` specified by the predicate,
+    /// passing a mutable reference to it.
+    ///
+    /// Elements outside `range` are not visited and always retained.
+    /// Elements before `range` are not moved.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or
+    /// if the end point is greater than the length of the vector.
+    ///
+    /// ```
+    /// # use retain_mut::VecExt;
+    /// let mut vec = vec![1, 2, 3, 4, 5, 6];
+    /// vec.retain_mut_range(1..5, |x| { *x *= 10; *x % 20 == 0 });
+    /// assert_eq!(vec, [1, 20, 40, 6]);
+    /// ```
+    fn retain_mut_range<R, F>(&mut self, range: R, f: F)
+    where
+        R: RangeBounds<usize>,
+        F: FnMut(&mut T) -> bool;
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it,
+    /// without preserving the order of the retained elements.
+    ///
+    /// When an element is rejected, it's removed with [`swap_remove`](Vec::swap_remove),
+    /// i.e. the last element is moved into its place and visited next.
+    /// Each element is still visited exactly once, and each removal is O(1).
+    ///
+    /// ```
+    /// # use retain_mut::VecExt;
+    /// let mut vec = vec![1, 2, 3, 4, 5, 6];
+    /// vec.retain_mut_swap(|x| *x % 2 == 0);
+    /// assert_eq!(vec, [6, 2, 4]);
+    /// ```
+    fn retain_mut_swap<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool;
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it,
+    /// without preserving the order of the retained elements.
+    ///
+    /// The first elements are retained like [`retain_mut_swap`](VecExt::retain_mut_swap)
+    /// as a sample. If few enough of them are removed, the rest are retained the same way,
+    /// which moves one element per removal; otherwise they are retained like
+    /// [`RetainMut::retain_mut`], which moves one element per retained element.
+    ///
+    /// The threshold depends on the size of the elements, following the crossover points
+    /// measured by `examples/adaptive_crossover.rs`: swap removal is used below 25% removal
+    /// for elements of up to 32 bytes, below 65% for up to 64 bytes, and below 80% for
+    /// larger ones. For elements of up to 16 bytes, swap removal was never reliably faster,
+    /// so `retain_mut` is always used for them without sampling.
+    ///
+    /// Each element is still visited exactly once, but the visiting order is unspecified.
+    ///
+    /// ```
+    /// # use retain_mut::VecExt;
+    /// let mut vec: Vec<u128> = (0..1000).collect();
+    /// vec.retain_mut_unordered_adaptive(|x| { *x *= 2; *x % 3 != 0 });
+    /// vec.sort();
+    /// assert_eq!(vec.len(), 666);
+    /// assert!(vec.iter().all(|x| x % 2 == 0 && x % 3 != 0));
+    /// ```
+    fn retain_mut_unordered_adaptive<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool;
+
+    /// Consumes the vector, passing a mutable reference of each element to the predicate,
+    /// and splits the elements into the retained ones and the removed ones.
+    ///
+    /// Both returned vectors preserve the original order. The retained elements
+    /// reuse the allocation of the original vector.
+    ///
+    /// ```
+    /// # use retain_mut::VecExt;
+    /// let vec = vec![1, 2, 3, 4, 5, 6];
+    /// let (now, later) = vec.partition_mut(|x| { *x *= 10; *x <= 30 });
+    /// assert_eq!(now, [10, 20, 30]);
+    /// assert_eq!(later, [40, 50, 60]);
+    /// ```
+    fn partition_mut<F>(self, f: F) -> (Vec<T>, Vec<T>)
+    where
+        Self: Sized,
+        F: FnMut(&mut T) -> bool;
+
+    /// Retains only the elements specified by the fallible predicate,
+    /// passing a mutable reference to it, and returns all the errors.
+    ///
+    /// Unlike [`try_retain_mut`](RetainMut::try_retain_mut), this visits all the elements.
+    /// Elements the predicate failed on are retained, and the errors are returned
+    /// in the order of the elements.
+    ///
+    /// ```
+    /// # use retain_mut::VecExt;
+    /// let mut vec = vec!["1", "2", "x", "4", "y"];
+    /// let errs = vec.retain_mut_collect_errs(|s| s.parse::<i32>().map(|x| x % 2 == 0));
+    /// assert_eq!(errs.len(), 2);
+    /// assert_eq!(vec, ["2", "x", "4", "y"]);
+    /// ```
+    fn retain_mut_collect_errs<F, E>(&mut self, f: F) -> Vec<E>
+    where
+        F: FnMut(&mut T) -> Result<bool, E>;
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it,
+    /// and returns the original indices of the removed elements in ascending order.
+    ///
+    /// This works like [`RetainMut::retain_mut`] otherwise, so the same removals can be
+    /// applied to another collection afterwards.
+    ///
+    /// ```
+    /// # use retain_mut::VecExt;
+    /// let mut vec = vec![1, 2, 3, 4, 5, 6];
+    /// let removed = vec.retain_mut_indices(|x| { *x *= 10; *x % 20 == 0 });
+    /// assert_eq!(removed, [0, 2, 4]);
+    /// assert_eq!(vec, [20, 40, 60]);
+    /// ```
+    fn retain_mut_indices<F>(&mut self, f: F) -> Vec<usize>
+    where
+        F: FnMut(&mut T) -> bool;
+
+    /// Retains only the elements specified by the predicate, passing the original index,
+    /// the original length of the vector, and a mutable reference of each element to it.
+    ///
+    /// This works like [`RetainMut::retain_mut_indexed`], without the need to capture
+    /// the length before the call.
+    ///
+    /// ```
+    /// # use retain_mut::VecExt;
+    /// let mut vec: Vec<_> = (0..20).collect();
+    /// // Keep the last 10%.
+    /// vec.retain_mut_indexed_len(|i, len, _| i * 10 >= len * 9);
+    /// assert_eq!(vec, [18, 19]);
+    /// ```
+    fn retain_mut_indexed_len<F>(&mut self, f: F)
+    where
+        F: FnMut(usize, usize, &mut T) -> bool;
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it,
+    /// and returns the decisions as a mask over the original positions,
+    /// where `true` means the element at that position was retained.
+    ///
+    /// This works like [`RetainMut::retain_mut`] otherwise, so the same selection can be
+    /// applied to other collections afterwards.
+    ///
+    /// ```
+    /// # use retain_mut::VecExt;
+    /// let mut vec = vec![1, 2, 3, 4];
+    /// let mask = vec.retain_mut_mask(|x| { *x *= 10; *x % 20 == 0 });
+    /// assert_eq!(mask, [false, true, false, true]);
+    /// assert_eq!(vec, [20, 40]);
+    ///
+    /// let mut names = vec!["a", "b", "c", "d"];
+    /// let mut mask = mask.into_iter();
+    /// names.retain(|_| mask.next().unwrap());
+    /// assert_eq!(names, ["b", "d"]);
+    /// ```
+    fn retain_mut_mask<F>(&mut self, f: F) -> Vec<bool>
+    where
+        F: FnMut(&mut T) -> bool;
+
+    /// Moves each element into the predicate, and retains the element it returns in place,
+    /// or removes it if `None` is returned.
+    ///
+    /// This is an in-place `filter_map` into the same type, which preserves the order
+    /// and reuses the allocation. Since the element is moved into the predicate,
+    /// it can be transformed into a new value freely. If the predicate panics, the element
+    /// it panicked on is lost, while the elements already decided on and the unvisited ones
+    /// are retained.
+    ///
+    /// ```
+    /// # use retain_mut::VecExt;
+    /// let mut vec = vec![String::from("a"), String::from(""), String::from("c")];
+    /// vec.retain_mut_replace(|s| if s.is_empty() { None } else { Some(s + "!") });
+    /// assert_eq!(vec, ["a!", "c!"]);
+    /// ```
+    #[doc(alias = "filter_map_in_place")]
+    fn retain_mut_replace<F>(&mut self, f: F)
+    where
+        F: FnMut(T) -> Option<T>;
+
+    /// Retains only the elements specified by the predicate, deciding on
+    /// contiguous batches of up to `batch` elements at a time.
+    ///
+    /// The predicate is given a mutable slice of elements and a mask of the same length,
+    /// whose items are all `true` initially. It sets an item to `false` to remove
+    /// the corresponding element. The elements are compacted after each batch is decided,
+    /// which can help vectorizing a cheap predicate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch` is zero.
+    ///
+    /// ```
+    /// # use retain_mut::VecExt;
+    /// let mut vec: Vec<u32> = (0..10).collect();
+    /// vec.retain_mut_batched(4, |xs, mask| {
+    ///     for (x, keep) in xs.iter_mut().zip(mask) {
+    ///         *x *= 10;
+    ///         *keep = *x % 20 == 0;
+    ///     }
+    /// });
+    /// assert_eq!(vec, [0, 20, 40, 60, 80]);
+    /// ```
+    fn retain_mut_batched<F>(&mut self, batch: usize, f: F)
+    where
+        F: FnMut(&mut [T], &mut [bool]);
+
+    /// Retains only the elements specified by the predicate, passing a reference to
+    /// the last retained element and a mutable reference to the current one.
+    ///
+    /// The last retained element is `None` until any element is retained.
+    ///
+    /// ```
+    /// # use retain_mut::VecExt;
+    /// let mut vec = vec![1.0, 1.05, 1.1, 2.0, 2.03, 3.0];
+    /// // Drop near-duplicates of the last kept element.
+    /// vec.retain_mut_windowed(|prev, x| prev.map_or(true, |prev| *x - *prev > 0.08));
+    /// assert_eq!(vec, [1.0, 1.1, 2.0, 3.0]);
+    /// ```
+    fn retain_mut_windowed<F>(&mut self, f: F)
+    where
+        F: FnMut(Option<&T>, &mut T) -> bool;
+
+    /// Removes consecutive elements with the same key, and retains only the elements
+    /// specified by the predicate, passing a mutable reference to it.
+    ///
+    /// Among each run of consecutive elements with equal keys, only the first one
+    /// the predicate returns `true` on is retained. The key of each element is computed
+    /// before the predicate is called on it, and the predicate is not called on the
+    /// elements after the retained one in the same run.
+    ///
+    /// ```
+    /// # use retain_mut::VecExt;
+    /// let mut vec = vec![(1, 0), (1, 1), (1, 2), (2, 3), (3, 4), (3, 5)];
+    /// let mut visited = 0;
+    /// vec.dedup_retain_mut(|x| x.0, |x| { visited += 1; x.1 *= 10; x.1 != 0 });
+    /// assert_eq!(vec, [(1, 10), (2, 30), (3, 40)]);
+    /// assert_eq!(visited, 4);
+    /// ```
+    fn dedup_retain_mut<K, KF, F>(&mut self, key: KF, f: F)
+    where
+        K: PartialEq,
+        KF: FnMut(&T) -> K,
+        F: FnMut(&mut T) -> bool;
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it,
+    /// and returns the number of slots freed, i.e. the number of removed elements.
+    ///
+    /// This works like [`RetainMut::retain_mut`], and guarantees that the capacity of the vector
+    /// is left unchanged, even if the predicate panics. So pushing back as many elements as
+    /// returned never reallocates.
+    ///
+    /// ```
+    /// # use retain_mut::VecExt;
+    /// let mut vec = Vec::with_capacity(8);
+    /// vec.extend(1..=6);
+    /// let capacity = vec.capacity();
+    /// let freed = vec.retain_mut_keep_capacity(|x| { *x *= 10; *x % 20 == 0 });
+    /// assert_eq!(freed, 3);
+    /// vec.extend((0..freed).map(|x| x as i32));
+    /// assert_eq!(vec, [20, 40, 60, 0, 1, 2]);
+    /// assert_eq!(vec.capacity(), capacity);
+    /// ```
+    fn retain_mut_keep_capacity<F>(&mut self, f: F) -> usize
+    where
+        F: FnMut(&mut T) -> bool;
+
+    /// Removes and returns the first element the predicate returns `true` on,
+    /// passing a mutable reference of each element to it.
+    ///
+    /// The predicate is not called on the elements after the removed one,
+    /// which are shifted to preserve the order. Returns `None` if the predicate
+    /// returns `false` on all the elements, which are all retained.
+    ///
+    /// ```
+    /// # use retain_mut::VecExt;
+    /// let mut vec = vec![1, 2, 3, 4];
+    /// assert_eq!(vec.remove_first_mut(|x| { *x *= 10; *x > 15 }), Some(20));
+    /// assert_eq!(vec, [10, 3, 4]);
+    /// assert_eq!(vec.remove_first_mut(|x| *x > 100), None);
+    /// ```
+    fn remove_first_mut<F>(&mut self, f: F) -> Option<T>
+    where
+        F: FnMut(&mut T) -> bool;
+
+    /// Retains only the elements specified by the asynchronous predicate,
+    /// passing a mutable reference to it.
+    ///
+    /// The predicate is called on each element in order, and the returned future is awaited
+    /// before moving on to the next element. If the returned future is dropped before completion,
+    /// the elements already rejected are removed, while all the others are retained.
+    ///
+    /// If the returned future is leaked (e.g. with `mem::forget`), the vector may have
+    /// lost and leaked elements arbitrarily.
+    ///
+    /// ```edition2021
+    /// # use std::future::Future;
+    /// # use std::pin::pin;
+    /// # use std::task::{Context, Poll, Waker};
+    /// # fn block_on<F: Future>(fut: F) -> F::Output {
+    /// #     let mut fut = pin!(fut);
+    /// #     let mut cx = Context::from_waker(Waker::noop());
+    /// #     loop {
+    /// #         if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+    /// #             return output;
+    /// #         }
+    /// #     }
+    /// # }
+    /// # use retain_mut::VecExt;
+    /// async fn is_valid(x: i32) -> bool {
+    ///     x % 20 == 0
+    /// }
+    /// # block_on(async {
+    /// let mut vec = vec![1, 2, 3, 4];
+    /// vec.retain_mut_async(|x| { *x *= 10; is_valid(*x) }).await;
+    /// assert_eq!(vec, [20, 40]);
+    /// # });
+    /// ```
+    #[cfg(feature = "async")]
+    fn retain_mut_async<F, Fut>(&mut self, f: F) -> RetainMutAsync<'_, T, F, Fut>
+    where
+        F: FnMut(&mut T) -> Fut,
+        Fut: Future<Output = bool>;
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it,
+    /// evaluating the predicate on the elements in parallel.
+    ///
+    /// The predicate must be `Fn` since it can be called from multiple threads at once, and
+    /// the order of evaluation is unspecified. After all the elements are decided on,
+    /// the retained ones are compacted sequentially, preserving their order.
+    /// If the predicate panics, no element is removed.
+    ///
+    /// ```
+    /// # use retain_mut::VecExt;
+    /// let mut vec: Vec<u32> = (0..1000).collect();
+    /// vec.par_retain_mut(|x| { *x *= 2; *x % 3 == 0 });
+    /// assert_eq!(vec, (0..1000).map(|x| x * 2).filter(|x| x % 3 == 0).collect::<Vec<_>>());
+    /// ```
+    #[cfg(feature = "rayon")]
+    fn par_retain_mut<F>(&mut self, f: F)
+    where
+        T: Send,
+        F: Fn(&mut T) -> bool + Sync;
+}
+
+/// Retains only the elements specified by the predicate in two vectors in lockstep,
+/// passing mutable references of the elements at the same index to it.
+///
+/// In other words, remove the elements at all indices `i` such that
+/// `f(&mut a[i], &mut b[i])` returns `false` from both vectors,
+/// so that they stay aligned. The order of the retained elements is preserved.
+///
+/// # Panics
+///
+/// Panics if the two vectors have different lengths.
+///
+/// ```
+/// # use retain_mut::vec::retain_mut_zip;
+/// let mut ids = vec![1, 2, 3, 4];
+/// let mut hps = vec![10, 0, 5, 1];
+/// retain_mut_zip(&mut ids, &mut hps, |_, hp| { *hp -= 1; *hp > 0 });
+/// assert_eq!(ids, [1, 3]);
+/// assert_eq!(hps, [9, 4]);
+/// ```
+pub fn retain_mut_zip<A, B, F>(a: &mut Vec<A>, b: &mut Vec<B>, f: F)
+where
+    F: FnMut(&mut A, &mut B) -> bool,
+{
+    backshift::retain_mut_zip(a, b, f);
+}
+
+/// An iterator which uses a closure to determine if an element should be removed.
+///
+/// This struct is created by [`VecExt::extract_if`]. See its documentation for more.
+///
+/// The vector is only visited as far as the iterator is advanced. When the iterator is dropped,
+/// the remaining elements are moved to fill the holes left by the removed ones,
+/// so the vector contains the retained and the unvisited elements in their original order.
+///
+/// It can be stored and advanced on demand, e.g. with a function pointer as the predicate:
+///
+/// ```
+/// # use retain_mut::{RetainMutIter, VecExt};
+/// struct Evictor<'a> {
+///     evicted: RetainMutIter<'a, u32, fn(&mut u32) -> bool>,
+/// }
+///
+/// let mut vec = vec![1, 2, 3, 4, 5];
+/// let mut evictor = Evictor {
+///     evicted: VecExt::extract_if(&mut vec, |x| *x % 2 == 1),
+/// };
+/// assert_eq!(evictor.evicted.next(), Some(1));
+/// assert_eq!(evictor.evicted.next(), Some(3));
+/// drop(evictor);
+/// assert_eq!(vec, [2, 4, 5]);
+/// ```
+pub struct ExtractIf<'a, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    inner: backshift::ExtractIf<'a, Vec<T>, F>,
+}
+
+impl<T, F> Iterator for ExtractIf<'_, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An alias of [`ExtractIf`] for use as a general retaining iterator.
+pub type RetainMutIter<'a, T, F> = ExtractIf<'a, T, F>;
+
+/// A future which retains elements of a vector with an asynchronous predicate.
+///
+/// This struct is created by [`VecExt::retain_mut_async`]. See its documentation for more.
+#[cfg(feature = "async")]
+pub struct RetainMutAsync<'a, T, F, Fut> {
+    inner: backshift::RetainMutAsync<'a, Vec<T>, F, Fut>,
+}
+
+#[cfg(feature = "async")]
+impl<T, F, Fut> Future for RetainMutAsync<'_, T, F, Fut>
+where
+    F: FnMut(&mut T) -> Fut,
+    Fut: Future<Output = bool>,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        // SAFETY: `inner` is pinned since `self` is pinned.
+        unsafe { self.map_unchecked_mut(|s| &mut s.inner) }.poll(cx)
+    }
+}
+
+/// `retain_mut` is the in-place alternative to collecting `iter().filter_map(...)` into
+/// a new vector of the same type, when the elements only need to be mutated and filtered.
+/// The order is preserved and no element is cloned, nor is a new buffer allocated.
+/// To replace each element with a new value instead, see [`VecExt::retain_mut_replace`].
+///
+/// ```
+/// # use retain_mut::RetainMut;
+/// let mut words = vec![String::from("foo"), String::from(""), String::from("bar")];
+/// RetainMut::retain_mut(&mut words, |s| {
+///     if s.is_empty() {
+///         return false;
+///     }
+///     s.make_ascii_uppercase();
+///     true
+/// });
+/// assert_eq!(words, ["FOO", "BAR"]);
+/// ```
+///
+/// Zero-sized elements are supported like any other elements: each rejected element
+/// is dropped exactly once, even if the predicate panics.
+///
+/// ```
+/// # use retain_mut::RetainMut;
+/// # use std::cell::Cell;
+/// # use std::panic::{self, AssertUnwindSafe};
+/// thread_local!(static DROPS: Cell<usize> = Cell::new(0));
+/// struct Zst;
+/// impl Drop for Zst {
+///     fn drop(&mut self) {
+///         DROPS.with(|d| d.set(d.get() + 1));
+///     }
+/// }
+///
+/// let mut vec: Vec<_> = (0..6).map(|_| Zst).collect();
+/// let mut visited = 0;
+/// let result = panic::catch_unwind(AssertUnwindSafe(|| {
+///     vec.retain_mut_count(|_| {
+///         visited += 1;
+///         if visited == 4 {
+///             panic!();
+///         }
+///         visited % 2 == 0
+///     })
+/// }));
+/// assert!(result.is_err());
+/// assert_eq!(vec.len(), 4);
+/// assert_eq!(DROPS.with(|d| d.get()), 2);
+/// drop(vec);
+/// assert_eq!(DROPS.with(|d| d.get()), 6);
+/// ```
+///
+/// This includes plain zero-sized elements without a destructor.
+///
+/// ```
+/// # use retain_mut::RetainMut;
+/// let mut vec = vec![(); 10];
+/// let mut idx = 0;
+/// let removed = vec.retain_mut_count(|_| { idx += 1; idx % 3 == 0 });
+/// assert_eq!(removed, 7);
+/// assert_eq!(vec.len(), 3);
+/// ```
+#[allow(deprecated)]
+impl<T> RetainMut<T> for Vec<T> {
+    fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        backshift::retain_mut(self, f);
+    }
+
+    fn try_retain_mut<F, E>(&mut self, f: F) -> Result<(), E>
+    where
+        F: FnMut(&mut T) -> Result<bool, E>,
+    {
+        backshift::try_retain_mut(self, f).map(|_| ())
+    }
+
+    fn retain_mut_count<F>(&mut self, f: F) -> usize
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        backshift::retain_mut(self, f)
+    }
+
+    fn retain_mut_while<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let len = self.iter_mut().position(|x| !f(x)).unwrap_or(self.len());
+        self.truncate(len);
+    }
+}
+
+impl<T> VecExt<T> for Vec<T> {
+    fn retain_mut_rev<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        backshift::retain_mut_rev(self, f);
+    }
+
+    fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, T, F>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        ExtractIf {
+            inner: backshift::ExtractIf::new(self, f),
+        }
+    }
+
+    fn retain_mut_extract<F>(&mut self, mut f: F) -> Vec<T>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        VecExt::extract_if(self, |x| !f(x)).collect()
+    }
+
+    fn retain_mut_into<F, C>(&mut self, dest: &mut C, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+        C: Extend<T>,
+    {
+        dest.extend(VecExt::extract_if(self, |x| !f(x)));
+    }
+
+    fn retain_mut_range<R, F>(&mut self, range: R, f: F)
+    where
+        R: RangeBounds<usize>,
+        F: FnMut(&mut T) -> bool,
+    {
+        let range = range::check_range(range, self.len());
+        backshift::retain_mut_range(self, range, f);
+    }
+
+    fn retain_mut_swap<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        retain_swap(self, 0, f);
+    }
+
+    fn retain_mut_unordered_adaptive<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        if mem::size_of::<T>() <= ADAPTIVE_MAX_BACKSHIFT_SIZE {
+            backshift::retain_mut(self, f);
+            return;
+        }
+        // Sample with swap removal, so that switching to the backshift algorithm afterwards
+        // doesn't need to shift all the unvisited elements to fill the holes in the sample.
+        let mut idx = 0;
+        let mut visited = 0;
+        let mut removed = 0;
+        while idx < self.len() && visited < ADAPTIVE_SAMPLE_LEN {
+            visited += 1;
+            if f(&mut self[idx]) {
+                idx += 1;
+            } else {
+                self.swap_remove(idx);
+                removed += 1;
+            }
+        }
+        if removed * 100 < visited * adaptive_swap_percent(mem::size_of::<T>()) {
+            retain_swap(self, idx, f);
+        } else {
+            let len = self.len();
+            backshift::retain_mut_range(self, idx..len, f);
+        }
+    }
+
+    fn partition_mut<F>(mut self, f: F) -> (Vec<T>, Vec<T>)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let removed = self.retain_mut_extract(f);
+        (self, removed)
+    }
+
+    fn retain_mut_collect_errs<F, E>(&mut self, mut f: F) -> Vec<E>
+    where
+        F: FnMut(&mut T) -> Result<bool, E>,
+    {
+        let mut errs = Vec::new();
+        backshift::retain_mut(self, |x| {
+            f(x).unwrap_or_else(|e| {
+                errs.push(e);
+                true
+            })
+        });
+        errs
+    }
+
+    fn retain_mut_indices<F>(&mut self, mut f: F) -> Vec<usize>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let mut removed = Vec::new();
+        let mut idx = 0;
+        backshift::retain_mut(self, |x| {
+            let keep = f(x);
+            if !keep {
+                removed.push(idx);
+            }
+            idx += 1;
+            keep
+        });
+        removed
+    }
+
+    fn retain_mut_indexed_len<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, usize, &mut T) -> bool,
+    {
+        let len = self.len();
+        let mut idx = 0;
+        backshift::retain_mut(self, |x| {
+            let keep = f(idx, len, x);
+            idx += 1;
+            keep
+        });
+    }
+
+    fn retain_mut_mask<F>(&mut self, mut f: F) -> Vec<bool>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let mut mask = Vec::with_capacity(self.len());
+        backshift::retain_mut(self, |x| {
+            let keep = f(x);
+            mask.push(keep);
+            keep
+        });
+        mask
+    }
+
+    fn retain_mut_replace<F>(&mut self, f: F)
+    where
+        F: FnMut(T) -> Option<T>,
+    {
+        backshift::retain_mut_replace(self, f);
+    }
+
+    fn retain_mut_batched<F>(&mut self, batch: usize, f: F)
+    where
+        F: FnMut(&mut [T], &mut [bool]),
+    {
+        assert!(batch > 0, "batch size must be positive");
+        let mut mask = Vec::new();
+        mask.resize(cmp::min(batch, self.len()), true);
+        backshift::retain_mut_batched(self, &mut mask, f);
+    }
+
+    fn retain_mut_windowed<F>(&mut self, f: F)
+    where
+        F: FnMut(Option<&T>, &mut T) -> bool,
+    {
+        backshift::retain_mut_windowed(self, f);
+    }
+
+    fn dedup_retain_mut<K, KF, F>(&mut self, mut key: KF, mut f: F)
+    where
+        K: PartialEq,
+        KF: FnMut(&T) -> K,
+        F: FnMut(&mut T) -> bool,
+    {
+        // The key of the current run, and whether an element of the run has been retained.
+        let mut run: Option<(K, bool)> = None;
+        backshift::retain_mut(self, |x| {
+            let k = key(x);
+            match run {
+                Some((ref run_key, ref mut retained)) if *run_key == k => {
+                    if *retained {
+                        return false;
+                    }
+                    *retained = f(x);
+                    *retained
+                }
+                _ => {
+                    let keep = f(x);
+                    run = Some((k, keep));
+                    keep
+                }
+            }
+        });
+    }
+
+    fn retain_mut_keep_capacity<F>(&mut self, f: F) -> usize
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let capacity = self.capacity();
+        // The backshift algorithm only moves elements and sets the length.
+        let removed = backshift::retain_mut(self, f);
+        debug_assert_eq!(self.capacity(), capacity);
+        removed
+    }
+
+    fn remove_first_mut<F>(&mut self, f: F) -> Option<T>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let idx = self.iter_mut().position(f)?;
+        Some(self.remove(idx))
+    }
+
+    #[cfg(feature = "async")]
+    fn retain_mut_async<F, Fut>(&mut self, f: F) -> RetainMutAsync<'_, T, F, Fut>
+    where
+        F: FnMut(&mut T) -> Fut,
+        Fut: Future<Output = bool>,
+    {
+        RetainMutAsync {
+            inner: backshift::RetainMutAsync::new(self, f),
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    fn par_retain_mut<F>(&mut self, f: F)
+    where
+        T: Send,
+        F: Fn(&mut T) -> bool + Sync,
+    {
+        let mask: Vec<bool> = self.par_iter_mut().map(&f).collect();
+        let mut mask = mask.into_iter();
+        self.retain(|_| mask.next().unwrap_or(true));
+    }
+}
+
+// The number of elements `retain_mut_unordered_adaptive` samples.
+const ADAPTIVE_SAMPLE_LEN: usize = 256;
+// `retain_mut_unordered_adaptive` always uses the backshift algorithm for elements
+// no larger than this, for which swap removal was never reliably faster in benchmarks.
+const ADAPTIVE_MAX_BACKSHIFT_SIZE: usize = 16;
+
+// Otherwise, it uses swap removal when less than this percentage of the sampled elements
+// are removed. The thresholds are the crossover points measured by
+// `examples/adaptive_crossover.rs`, which grow with the size of the elements.
+fn adaptive_swap_percent(size: usize) -> usize {
+    match size {
+        0..=32 => 25,
+        33..=64 => 65,
+        _ => 80,
+    }
+}
+
+// Values in `0..start` are already retained. The values from `start` are visited,
+// and the rejected ones are removed by `swap_remove`.
+fn retain_swap<T, F>(vec: &mut Vec<T>, start: usize, mut f: F)
+where
+    F: FnMut(&mut T) -> bool,
+{
+    // Values in `0..idx` are retained, and values in `idx..len` are unvisited.
+    let mut idx = start;
+    while idx < vec.len() {
+        if f(&mut vec[idx]) {
+            idx += 1;
+        } else {
+            vec.swap_remove(idx);
+        }
+    }
+}
@@ -0,0 +1,624 @@
+//! Extra retain methods for `VecDeque`.
+
+use alloc::collections::vec_deque::VecDeque;
+use alloc::vec::Vec;
+#[cfg(feature = "async")]
+use core::future::Future;
+use core::mem;
+use core::ops::{Range, RangeBounds};
+#[cfg(feature = "async")]
+use core::pin::Pin;
+#[cfg(feature = "async")]
+use core::task::{Context, Poll};
+#[cfg(feature = "rayon")]
+use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
+
+use range;
+#[allow(deprecated)]
+use RetainMut;
+
+/// Trait that provides more retain methods for `VecDeque`.
+pub trait VecDequeExt<T> {
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it,
+    /// like [`RetainMut::retain_mut`], but visiting the elements from the back to the front.
+    ///
+    /// The order of the retained elements is still preserved.
+    ///
+    /// ```
+    /// # use retain_mut::VecDequeExt;
+    /// # use std::collections::VecDeque;
+    /// let mut deque = VecDeque::from(vec![1, 5, 2, 4, 3]);
+    /// let mut max = 0;
+    /// // Only keep elements which are greater than all elements after them.
+    /// deque.retain_mut_rev(|x| if *x > max { max = *x; true } else { false });
+    /// assert_eq!(deque, [5, 4, 3]);
+    /// ```
+    fn retain_mut_rev<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool;
+
+    /// Creates an iterator which uses a closure to determine if an element should be removed,
+    /// passing a mutable reference to it.
+    ///
+    /// If the closure returns `true`, the element is removed and yielded.
+    /// If the closure returns `false`, the element will remain in the deque.
+    /// The retained elements keep their order. If the iterator is dropped before being
+    /// fully consumed, the unvisited elements are retained after the visited ones.
+    ///
+    /// Retained elements are moved to the back of the deque as they are visited, and
+    /// the unvisited ones are only rotated back behind them when the iterator is dropped.
+    /// So if the iterator is leaked (e.g. with `mem::forget`), no element is lost, but
+    /// the deque may be left with the unvisited elements in front of the retained ones.
+    ///
+    /// `VecDeque` has an unstable inherent `extract_if` method,
+    /// so calling this one with method call syntax may trigger an `unstable_name_collisions` warning.
+    ///
+    /// ```
+    /// # use retain_mut::VecDequeExt;
+    /// # use std::collections::VecDeque;
+    /// let mut deque = VecDeque::from(vec![1, 2, 3, 4, 5, 6]);
+    /// let odds: Vec<_> = VecDequeExt::extract_if(&mut deque, |x| { *x *= 10; *x % 20 != 0 }).collect();
+    /// assert_eq!(odds, [10, 30, 50]);
+    /// assert_eq!(deque, [20, 40, 60]);
+    /// ```
+    fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, T, F>
+    where
+        F: FnMut(&mut T) -> bool;
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it,
+    /// and returns the removed elements in their original order.
+    ///
+    /// The removed elements keep any modification made by the predicate.
+    ///
+    /// ```
+    /// # use retain_mut::VecDequeExt;
+    /// # use std::collections::VecDeque;
+    /// let mut deque = VecDeque::from(vec![1, 2, 3, 4, 5, 6]);
+    /// let removed = deque.retain_mut_extract(|x| { *x *= 10; *x % 20 == 0 });
+    /// assert_eq!(deque, [20, 40, 60]);
+    /// assert_eq!(removed, [10, 30, 50]);
+    /// ```
+    fn retain_mut_extract<F>(&mut self, f: F) -> Vec<T>
+    where
+        F: FnMut(&mut T) -> bool;
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it,
+    /// and moves the removed elements into `dest` in their original order.
+    ///
+    /// This works like [`retain_mut_extract`](VecDequeExt::retain_mut_extract),
+    /// but allows reusing an existing collection. If `dest.extend` panics,
+    /// the elements not yet handed to it are retained in the deque.
+    ///
+    /// ```
+    /// # use retain_mut::VecDequeExt;
+    /// # use std::collections::VecDeque;
+    /// let mut deque = VecDeque::from(vec![1, 2, 3, 4, 5, 6]);
+    /// let mut removed = vec![0];
+    /// deque.retain_mut_into(&mut removed, |x| { *x *= 10; *x % 20 == 0 });
+    /// assert_eq!(deque, [20, 40, 60]);
+    /// assert_eq!(removed, [0, 10, 30, 50]);
+    /// ```
+    fn retain_mut_into<F, C>(&mut self, dest: &mut C, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+        C: Extend<T>;
+
+    /// Retains only the elements in `range` specified by the predicate,
+    /// passing a mutable reference to it.
+    ///
+    /// Elements outside `range` are not visited and always retained.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or
+    /// if the end point is greater than the length of the deque.
+    ///
+    /// ```
+    /// # use retain_mut::VecDequeExt;
+    /// # use std::collections::VecDeque;
+    /// let mut deque = VecDeque::from(vec![3, 4, 5, 6]);
+    /// deque.push_front(2);
+    /// deque.push_front(1);
+    /// deque.retain_mut_range(1..5, |x| { *x *= 10; *x % 20 == 0 });
+    /// assert_eq!(deque, [1, 20, 40, 6]);
+    /// ```
+    ///
+    /// If the predicate panics, the elements rejected so far are still removed,
+    /// and all the others, including the one it panicked on, are retained.
+    ///
+    /// ```
+    /// # use retain_mut::VecDequeExt;
+    /// # use std::cell::Cell;
+    /// # use std::collections::VecDeque;
+    /// # use std::panic::{self, AssertUnwindSafe};
+    /// thread_local!(static DROPS: Cell<usize> = Cell::new(0));
+    /// struct D(i32);
+    /// impl Drop for D {
+    ///     fn drop(&mut self) {
+    ///         DROPS.with(|d| d.set(d.get() + 1));
+    ///     }
+    /// }
+    ///
+    /// // Make the deque wrap around its buffer.
+    /// let mut deque: VecDeque<_> = (2..6).map(D).collect();
+    /// deque.push_front(D(1));
+    /// deque.push_front(D(0));
+    /// let mut visited = 0;
+    /// let result = panic::catch_unwind(AssertUnwindSafe(|| {
+    ///     deque.retain_mut_range(1.., |x| {
+    ///         visited += 1;
+    ///         if visited == 3 {
+    ///             panic!();
+    ///         }
+    ///         x.0 % 2 == 0
+    ///     });
+    /// }));
+    /// assert!(result.is_err());
+    /// assert!(deque.iter().map(|x| x.0).eq(vec![0, 2, 3, 4, 5]));
+    /// assert_eq!(DROPS.with(|d| d.get()), 1);
+    /// drop(deque);
+    /// assert_eq!(DROPS.with(|d| d.get()), 6);
+    /// ```
+    fn retain_mut_range<R, F>(&mut self, range: R, f: F)
+    where
+        R: RangeBounds<usize>,
+        F: FnMut(&mut T) -> bool;
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it,
+    /// without preserving the order of the retained elements.
+    ///
+    /// When an element is rejected, it's removed with [`swap_remove_back`](VecDeque::swap_remove_back),
+    /// i.e. the back element is moved into its place and visited next.
+    /// Each element is still visited exactly once, and each removal is O(1).
+    ///
+    /// ```
+    /// # use retain_mut::VecDequeExt;
+    /// # use std::collections::VecDeque;
+    /// let mut deque = VecDeque::from(vec![1, 2, 3, 4, 5, 6]);
+    /// deque.retain_mut_swap(|x| *x % 2 == 0);
+    /// assert_eq!(deque, [6, 2, 4]);
+    /// ```
+    fn retain_mut_swap<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool;
+
+    /// Retains only the elements specified by the asynchronous predicate,
+    /// passing a mutable reference to it.
+    ///
+    /// The predicate is called on each element in order, and the returned future is awaited
+    /// before moving on to the next element. If the returned future is dropped before completion,
+    /// the elements already rejected are removed, while all the others are retained in order.
+    ///
+    /// Retained elements are moved to the back of the deque as they are visited, so if
+    /// the returned future is leaked (e.g. with `mem::forget`), the deque may be left with
+    /// the unvisited elements in front of the retained ones.
+    ///
+    /// ```edition2021
+    /// # use std::collections::VecDeque;
+    /// # use std::future::{self, Future};
+    /// # use std::task::{Context, Waker};
+    /// # use retain_mut::VecDequeExt;
+    /// let mut cx = Context::from_waker(Waker::noop());
+    /// let mut deque = VecDeque::from(vec![1, 2, 3, 4, 5]);
+    /// let mut fut = Box::pin(deque.retain_mut_async(|x| {
+    ///     let x = *x;
+    ///     async move {
+    ///         if x == 4 {
+    ///             future::pending::<()>().await;
+    ///         }
+    ///         x % 2 == 1
+    ///     }
+    /// }));
+    /// assert!(fut.as_mut().poll(&mut cx).is_pending());
+    /// drop(fut);
+    /// assert_eq!(deque, [1, 3, 4, 5]);
+    /// ```
+    #[cfg(feature = "async")]
+    fn retain_mut_async<F, Fut>(&mut self, f: F) -> RetainMutAsync<'_, T, F, Fut>
+    where
+        F: FnMut(&mut T) -> Fut,
+        Fut: Future<Output = bool>;
+
+    /// Retains only the elements specified by the predicate, passing a mutable reference to it,
+    /// evaluating the predicate on the elements in parallel.
+    ///
+    /// The deque is made contiguous first. The predicate must be `Fn` since it can be called
+    /// from multiple threads at once, and the order of evaluation is unspecified.
+    /// After all the elements are decided on, the retained ones are compacted sequentially,
+    /// preserving their order. If the predicate panics, no element is removed.
+    ///
+    /// ```
+    /// # use retain_mut::VecDequeExt;
+    /// # use std::collections::VecDeque;
+    /// let mut deque: VecDeque<u32> = (0..1000).collect();
+    /// deque.par_retain_mut(|x| { *x *= 2; *x % 3 == 0 });
+    /// assert!(deque.into_iter().eq((0..1000).map(|x| x * 2).filter(|x| x % 3 == 0)));
+    /// ```
+    #[cfg(feature = "rayon")]
+    fn par_retain_mut<F>(&mut self, f: F)
+    where
+        T: Send,
+        F: Fn(&mut T) -> bool + Sync;
+}
+
+/// An iterator which uses a closure to determine if an element should be removed.
+///
+/// This struct is created by [`VecDequeExt::extract_if`]. See its documentation for more.
+pub struct ExtractIf<'a, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    // Unvisited values are at the front of the deque, and the retained ones are
+    // moved to the back as they are visited.
+    deque: &'a mut VecDeque<T>,
+    /// The number of unvisited values.
+    remaining: usize,
+    pred: F,
+}
+
+impl<T, F> Iterator for ExtractIf<'_, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while self.remaining > 0 {
+            let extract = match self.deque.front_mut() {
+                Some(cur) => (self.pred)(cur),
+                None => unreachable!(),
+            };
+            // Update the counter *after* the predicate is called, so that if the predicate
+            // panics, the current value is kept as an unvisited one.
+            self.remaining -= 1;
+            let cur = self.deque.pop_front();
+            if extract {
+                return cur;
+            }
+            // This never reallocates since a value was just popped.
+            self.deque.extend(cur);
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
+    }
+}
+
+impl<T, F> Drop for ExtractIf<'_, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    fn drop(&mut self) {
+        // Move the unvisited values back behind the retained ones.
+        self.deque.rotate_left(self.remaining);
+    }
+}
+
+/// A future which retains elements of a deque with an asynchronous predicate.
+///
+/// This struct is created by [`VecDequeExt::retain_mut_async`]. See its documentation for more.
+#[cfg(feature = "async")]
+pub struct RetainMutAsync<'a, T, F, Fut> {
+    // Like `ExtractIf`, unvisited values are at the front of the deque, and the retained ones
+    // are moved to the back as they are visited.
+    deque: &'a mut VecDeque<T>,
+    /// The number of unvisited values.
+    remaining: usize,
+    f: F,
+    /// The pending decision on the value at the front of the deque.
+    fut: Option<Fut>,
+}
+
+#[cfg(feature = "async")]
+impl<T, F, Fut> Future for RetainMutAsync<'_, T, F, Fut>
+where
+    F: FnMut(&mut T) -> Fut,
+    Fut: Future<Output = bool>,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        // SAFETY: `fut` is never moved out, and only dropped in place.
+        let this = unsafe { self.get_unchecked_mut() };
+        loop {
+            let fut = match this.fut {
+                Some(ref mut fut) => fut,
+                None => {
+                    if this.remaining == 0 {
+                        return Poll::Ready(());
+                    }
+                    let cur = match this.deque.front_mut() {
+                        Some(cur) => cur,
+                        None => unreachable!(),
+                    };
+                    this.fut.get_or_insert((this.f)(cur))
+                }
+            };
+            // SAFETY: `fut` is pinned since `self` is pinned.
+            let keep = match unsafe { Pin::new_unchecked(fut) }.poll(cx) {
+                Poll::Ready(keep) => keep,
+                Poll::Pending => return Poll::Pending,
+            };
+            this.fut = None;
+            this.remaining -= 1;
+            let cur = this.deque.pop_front();
+            if keep {
+                // This never reallocates since a value was just popped.
+                this.deque.extend(cur);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T, F, Fut> Drop for RetainMutAsync<'_, T, F, Fut> {
+    fn drop(&mut self) {
+        // Move the unvisited values back behind the retained ones.
+        self.deque.rotate_left(self.remaining);
+    }
+}
+
+/// If the predicate panics, the elements rejected so far are dropped exactly once,
+/// and all the others, including the one it panicked on, are retained in order.
+///
+/// ```
+/// # use retain_mut::RetainMut;
+/// # use std::cell::Cell;
+/// # use std::collections::VecDeque;
+/// # use std::panic::{self, AssertUnwindSafe};
+/// thread_local!(static DROPS: Cell<usize> = Cell::new(0));
+/// struct D(i32);
+/// impl Drop for D {
+///     fn drop(&mut self) {
+///         DROPS.with(|d| d.set(d.get() + 1));
+///     }
+/// }
+///
+/// // Make the deque wrap around its buffer.
+/// let mut deque: VecDeque<_> = (2..6).map(D).collect();
+/// deque.push_front(D(1));
+/// deque.push_front(D(0));
+/// let mut visited = 0;
+/// let result = panic::catch_unwind(AssertUnwindSafe(|| {
+///     RetainMut::retain_mut(&mut deque, |x| {
+///         visited += 1;
+///         if visited == 3 {
+///             panic!();
+///         }
+///         x.0 % 2 == 1
+///     });
+/// }));
+/// assert!(result.is_err());
+/// assert!(deque.iter().map(|x| x.0).eq(vec![1, 2, 3, 4, 5]));
+/// assert_eq!(DROPS.with(|d| d.get()), 1);
+/// drop(deque);
+/// assert_eq!(DROPS.with(|d| d.get()), 6);
+/// ```
+#[allow(deprecated)]
+impl<T> RetainMut<T> for VecDeque<T> {
+    fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        retain_vec(self, |vec| RetainMut::retain_mut(vec, f));
+    }
+
+    fn retain_mut_count<F>(&mut self, f: F) -> usize
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        retain_vec(self, |vec| RetainMut::retain_mut_count(vec, f))
+    }
+
+    fn retain_mut_changed<F>(&mut self, f: F) -> bool
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        retain_vec(self, |vec| RetainMut::retain_mut_changed(vec, f))
+    }
+
+    fn retain_mut_indexed<F>(&mut self, f: F)
+    where
+        F: FnMut(usize, &mut T) -> bool,
+    {
+        retain_vec(self, |vec| RetainMut::retain_mut_indexed(vec, f));
+    }
+
+    fn retain_mut_while<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let len = self.iter_mut().position(|x| !f(x)).unwrap_or(self.len());
+        self.truncate(len);
+    }
+
+    fn try_retain_mut<F, E>(&mut self, f: F) -> Result<(), E>
+    where
+        F: FnMut(&mut T) -> Result<bool, E>,
+    {
+        retain_vec(self, |vec| RetainMut::try_retain_mut(vec, f))
+    }
+}
+
+// This drop guard converts the vector back to the deque, either when all elements have
+// been processed, or when predicate failed, or when predicate or `drop` of element panicked.
+struct RestoreOnDrop<'a, T> {
+    deque: &'a mut VecDeque<T>,
+    vec: Vec<T>,
+}
+
+impl<T> Drop for RestoreOnDrop<'_, T> {
+    fn drop(&mut self) {
+        *self.deque = VecDeque::from(mem::take(&mut self.vec));
+    }
+}
+
+// Converts the deque into a vector for `f` to retain with the backshift algorithm,
+// and converts it back afterwards. Neither conversion reallocates: converting to a vector
+// rearranges the elements in place if the deque wraps around its buffer, which is still
+// much cheaper than indexing and swapping element by element on the ring buffer.
+fn retain_vec<T, R, F>(deque: &mut VecDeque<T>, f: F) -> R
+where
+    F: FnOnce(&mut Vec<T>) -> R,
+{
+    let vec = Vec::from(mem::take(deque));
+    let mut g = RestoreOnDrop { deque, vec };
+    f(&mut g.vec)
+}
+
+// The implementation is based on
+// https://github.com/rust-lang/rust/blob/3e21768a0a3fc84befd1cbe825ae6849e9941b73/library/alloc/src/collections/vec_deque/mod.rs#L2148-L2180
+// Only values in `range` are visited.
+fn retain_deque<T, F>(deque: &mut VecDeque<T>, range: Range<usize>, mut f: F)
+where
+    F: FnMut(&mut T) -> bool,
+{
+    let end = range.end;
+    // Values in `g.start..g.end` are rejected ones, and values from `g.end` are unvisited.
+    // If predicate panicked, the rejected values are still dropped, and the others retained.
+    let mut g = DrainOnDrop {
+        deque,
+        start: range.start,
+        end: range.start,
+    };
+
+    // Stage 1: All values are retained.
+    while g.end < end {
+        let cur = g.end;
+        if !f(&mut g.deque[cur]) {
+            g.end += 1;
+            break;
+        }
+        g.start += 1;
+        g.end += 1;
+    }
+    // Stage 2: Swap retained value into the first rejected slot.
+    while g.end < end {
+        let cur = g.end;
+        if f(&mut g.deque[cur]) {
+            g.deque.swap(g.start, cur);
+            g.start += 1;
+        }
+        g.end += 1;
+    }
+    // Stage 3: The drop guard drops all values in `start..end`.
+}
+
+impl<T> VecDequeExt<T> for VecDeque<T> {
+    fn retain_mut_rev<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let len = self.len();
+        // Values in `0..g.start` are unvisited, values in `g.start..g.end` are rejected ones,
+        // and values in `g.end..len` are retained ones. The drop guard drops the rejected ones,
+        // either when all values are visited, or when predicate panicked.
+        let mut g = DrainOnDrop {
+            deque: self,
+            start: len,
+            end: len,
+        };
+        while g.start > 0 {
+            let cur = g.start - 1;
+            if f(&mut g.deque[cur]) {
+                g.end -= 1;
+                if cur != g.end {
+                    g.deque.swap(cur, g.end);
+                }
+            }
+            g.start = cur;
+        }
+    }
+
+    fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, T, F>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        ExtractIf {
+            remaining: self.len(),
+            deque: self,
+            pred: f,
+        }
+    }
+
+    fn retain_mut_extract<F>(&mut self, mut f: F) -> Vec<T>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        VecDequeExt::extract_if(self, |x| !f(x)).collect()
+    }
+
+    fn retain_mut_into<F, C>(&mut self, dest: &mut C, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+        C: Extend<T>,
+    {
+        dest.extend(VecDequeExt::extract_if(self, |x| !f(x)));
+    }
+
+    fn retain_mut_range<R, F>(&mut self, range: R, f: F)
+    where
+        R: RangeBounds<usize>,
+        F: FnMut(&mut T) -> bool,
+    {
+        let range = range::check_range(range, self.len());
+        retain_deque(self, range, f);
+    }
+
+    fn retain_mut_swap<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        // Values in `0..idx` are retained, and values in `idx..len` are unvisited.
+        let mut idx = 0;
+        while idx < self.len() {
+            if f(&mut self[idx]) {
+                idx += 1;
+            } else {
+                self.swap_remove_back(idx);
+            }
+        }
+    }
+
+    #[cfg(feature = "async")]
+    fn retain_mut_async<F, Fut>(&mut self, f: F) -> RetainMutAsync<'_, T, F, Fut>
+    where
+        F: FnMut(&mut T) -> Fut,
+        Fut: Future<Output = bool>,
+    {
+        RetainMutAsync {
+            remaining: self.len(),
+            deque: self,
+            f,
+            fut: None,
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    fn par_retain_mut<F>(&mut self, f: F)
+    where
+        T: Send,
+        F: Fn(&mut T) -> bool + Sync,
+    {
+        let mask: Vec<bool> = self.make_contiguous().par_iter_mut().map(&f).collect();
+        let mut mask = mask.into_iter();
+        self.retain(|_| mask.next().unwrap_or(true));
+    }
+}
+
+// This drop guard will be invoked when all values are processed,
+// or when predicate panicked. It drops the rejected values in `start..end`,
+// and shifts the values after them to fill the gap.
+struct DrainOnDrop<'a, T> {
+    deque: &'a mut VecDeque<T>,
+    start: usize,
+    end: usize,
+}
+
+impl<T> Drop for DrainOnDrop<'_, T> {
+    fn drop(&mut self) {
+        self.deque.drain(self.start..self.end);
+    }
+}